@@ -0,0 +1,148 @@
+use std::fmt;
+use crate::{Pattern, Index, reindex_from};
+use crate::rewriting::{Rule, RewriteSystem, Equation};
+use crate::simplification::{Comparison, ReductionOrder};
+use crate::confluence::overlaps;
+
+/// Completion failure.
+pub enum CompletionError<F, X> {
+	/// An equation could not be oriented by the reduction order.
+	///
+	/// Gives the two sides of the equation, in normal form.
+	Unorientable(Pattern<F, X>, Pattern<F, X>),
+
+	/// The maximum number of rules has been generated without reaching a convergent system.
+	///
+	/// Gives the current rules.
+	Divergence(RewriteSystem<F, X>)
+}
+
+impl<F: fmt::Debug, X: fmt::Debug> fmt::Debug for CompletionError<F, X> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			CompletionError::Unorientable(s, t) => write!(f, "Unorientable({:?}, {:?})", s, t),
+			CompletionError::Divergence(rules) => write!(f, "Divergence({:?})", rules)
+		}
+	}
+}
+
+impl<F: fmt::Display, X: fmt::Display> fmt::Display for CompletionError<F, X> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			CompletionError::Unorientable(s, t) => write!(f, "unable to orient equation {} = {}", s, t),
+			CompletionError::Divergence(rules) => write!(f, "completion did not converge within the rule limit ({} current rules)", rules.len())
+		}
+	}
+}
+
+impl<F: fmt::Debug + fmt::Display, X: fmt::Debug + fmt::Display> std::error::Error for CompletionError<F, X> {}
+
+/// Check if some sub-pattern of the pattern is an instance of the left-hand side.
+fn reducible<F: Clone + PartialEq, X: Clone + PartialEq>(lhs: &Pattern<F, X>, pattern: &Pattern<F, X>) -> bool {
+	pattern.pre_order().any(|sub| lhs.matches_like(sub).is_some())
+}
+
+/// Normalize the pattern, with variables treated as constants.
+fn normal_form<F: Clone + PartialEq, X: Clone + PartialEq>(rules: &RewriteSystem<F, X>, pattern: &Pattern<F, X>) -> Pattern<F, X> {
+	let mut pattern = pattern.clone();
+	while let Some(next) = rules.pattern_step(&pattern) {
+		pattern = next
+	}
+
+	pattern
+}
+
+impl<F: Clone + PartialEq, X: Index> RewriteSystem<F, X> {
+	/// Run the Knuth-Bendix completion procedure on the given equations.
+	///
+	/// Equations are oriented with the given reduction order,
+	/// and critical pairs are added until the system is confluent.
+	/// Rules are kept inter-reduced: no left-hand side is reducible by another rule,
+	/// and every right-hand side is in normal form.
+	/// The variables of each rule are renamed to consecutive indexes starting from `X::ZERO`.
+	///
+	/// Fails if an equation cannot be oriented,
+	/// or if more than `limit` rules are generated (the completion may not terminate).
+	pub fn complete<O: ReductionOrder<F, X>>(equations: &[Equation<F, X>], order: &O, limit: usize) -> Result<RewriteSystem<F, X>, CompletionError<F, X>> {
+		let mut equations: Vec<Equation<F, X>> = equations.iter().rev().cloned().collect();
+		let mut rules = RewriteSystem::new();
+
+		// Tells if the critical pairs of each rule have been computed.
+		let mut marked: Vec<bool> = Vec::new();
+		let mut generated = 0;
+
+		loop {
+			while let Some((s, t)) = equations.pop() {
+				let s = normal_form(&rules, &s);
+				let t = normal_form(&rules, &t);
+				if s == t {
+					continue
+				}
+
+				let (lhs, rhs) = match order.compare(&s, &t) {
+					Comparison::Greater => (s, t),
+					Comparison::Less => (t, s),
+					_ => return Err(CompletionError::Unorientable(s, t))
+				};
+
+				generated += 1;
+				if generated > limit {
+					return Err(CompletionError::Divergence(rules))
+				}
+
+				let (mut renamed, _) = reindex_from(&[&lhs, &rhs], X::ZERO);
+				let rhs = renamed.pop().unwrap();
+				let lhs = renamed.pop().unwrap();
+				let new_rule = Rule::new_unchecked(lhs, rhs);
+
+				// Inter-reduction.
+				let mut kept = RewriteSystem::new();
+				let mut kept_marked = Vec::new();
+				for (rule, m) in rules.rules().iter().zip(marked.iter()) {
+					if reducible(new_rule.lhs(), rule.lhs()) {
+						equations.push((rule.lhs().clone(), rule.rhs().clone()))
+					} else {
+						kept.add(rule.clone());
+						kept_marked.push(*m)
+					}
+				}
+
+				kept.add(new_rule);
+				kept_marked.push(false);
+				rules = kept.rules().iter().map(|rule| {
+					let rhs = normal_form(&kept, rule.rhs());
+					Rule::new_unchecked(rule.lhs().clone(), rhs)
+				}).collect();
+				marked = kept_marked
+			}
+
+			// Select the smallest rule whose critical pairs are not computed yet.
+			let selected = (0..rules.len()).filter(|i| !marked[*i]).min_by_key(|i| {
+				let rule = &rules.rules()[*i];
+				rule.lhs().size() + rule.rhs().size()
+			});
+
+			let i = match selected {
+				Some(i) => i,
+				None => return Ok(rules)
+			};
+
+			marked[i] = true;
+			let rule = &rules.rules()[i];
+			for (j, other) in rules.rules().iter().enumerate() {
+				if !marked[j] {
+					continue
+				}
+
+				let (a, next) = reindex_from(&[rule.lhs(), rule.rhs()], X::ZERO);
+				let (b, _) = reindex_from(&[other.lhs(), other.rhs()], next);
+				let mut pairs = overlaps((i, &a[0], &a[1]), (j, &b[0], &b[1]));
+				if i != j {
+					pairs.extend(overlaps((j, &b[0], &b[1]), (i, &a[0], &a[1])))
+				}
+
+				equations.extend(pairs.into_iter().map(|pair| (pair.left, pair.right)))
+			}
+		}
+	}
+}
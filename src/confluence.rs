@@ -0,0 +1,186 @@
+use std::collections::VecDeque;
+use crate::{Pattern, PatternKind, Position, Index, reindex_from};
+use crate::rewriting::RewriteSystem;
+
+/// A critical pair, obtained by overlapping the left-hand side of the inner rule
+/// into a non-variable position of the left-hand side of the outer rule.
+///
+/// The two rules are renamed apart before unification,
+/// so variables of the outer rule are renamed from `X::ZERO`, and variables of the inner rule follow.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CriticalPair<F, X> {
+	/// Index of the outer rule.
+	pub outer: usize,
+
+	/// Index of the inner rule.
+	pub inner: usize,
+
+	/// Position of the overlap in the left-hand side of the outer rule.
+	pub position: Position,
+
+	/// The overlapped pattern, rewritten by both rules.
+	pub peak: Pattern<F, X>,
+
+	/// Reduct of the peak by the inner rule, at the overlap position.
+	pub left: Pattern<F, X>,
+
+	/// Reduct of the peak by the outer rule, at the root.
+	pub right: Pattern<F, X>
+}
+
+/// Result of a joinability check.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Joinability {
+	/// The two patterns rewrite to a common pattern.
+	Joinable,
+
+	/// Every reduct of both patterns has been explored, and none is common.
+	NotJoinable,
+
+	/// No common reduct has been found within the steps limit.
+	Unknown
+}
+
+/// Every critical pair obtained by overlapping the inner rule into a non-variable position of the outer rule.
+///
+/// The rules must not share variables.
+/// When `outer == inner`, the root overlap is skipped.
+pub(crate) fn overlaps<F: Clone + PartialEq, X: Clone + PartialEq>(outer: (usize, &Pattern<F, X>, &Pattern<F, X>), inner: (usize, &Pattern<F, X>, &Pattern<F, X>)) -> Vec<CriticalPair<F, X>> {
+	let (i, l1, r1) = outer;
+	let (j, l2, r2) = inner;
+	let mut pairs = Vec::new();
+	for (position, sub) in l1.pre_order().with_positions() {
+		if let PatternKind::Var(_) = sub.kind() {
+			continue
+		}
+
+		if i == j && position.is_root() {
+			continue
+		}
+
+		if let Ok(sigma) = sub.unify(l2) {
+			pairs.push(CriticalPair {
+				outer: i,
+				inner: j,
+				peak: sigma.apply(l1),
+				left: sigma.apply(&l1.replace_at(&position, r2.clone()).unwrap()),
+				right: sigma.apply(r1),
+				position
+			})
+		}
+	}
+
+	pairs
+}
+
+impl<F: Clone + PartialEq, X: Clone + PartialEq> RewriteSystem<F, X> {
+	/// Every pattern obtained by one rewriting step, at any position and with any rule.
+	///
+	/// Variables of the pattern are treated as constants.
+	pub fn pattern_reducts(&self, pattern: &Pattern<F, X>) -> Vec<Pattern<F, X>> {
+		let mut reducts = Vec::new();
+		for (position, sub) in pattern.pre_order().with_positions() {
+			for rule in self.rules() {
+				if let Some(reduct) = rule.apply_pattern(sub) {
+					reducts.push(pattern.replace_at(&position, reduct).unwrap())
+				}
+			}
+		}
+
+		reducts
+	}
+
+	/// Check if the two patterns rewrite to a common pattern, exploring every reduction of at most `limit` steps.
+	pub fn joinability(&self, a: &Pattern<F, X>, b: &Pattern<F, X>, limit: usize) -> Joinability {
+		if a == b {
+			return Joinability::Joinable
+		}
+
+		// Reducts of each side, explored breadth-first.
+		let mut reached = [vec![a.clone()], vec![b.clone()]];
+		let mut queues = [VecDeque::new(), VecDeque::new()];
+		queues[0].push_back((a.clone(), 0));
+		queues[1].push_back((b.clone(), 0));
+
+		let mut complete = true;
+		while !queues[0].is_empty() || !queues[1].is_empty() {
+			for side in 0..2 {
+				if let Some((p, depth)) = queues[side].pop_front() {
+					let reducts = self.pattern_reducts(&p);
+					if depth >= limit {
+						complete &= reducts.is_empty();
+						continue
+					}
+
+					for reduct in reducts {
+						if reached[1 - side].contains(&reduct) {
+							return Joinability::Joinable
+						}
+
+						if !reached[side].contains(&reduct) {
+							reached[side].push(reduct.clone());
+							queues[side].push_back((reduct, depth + 1))
+						}
+					}
+				}
+			}
+		}
+
+		if complete {
+			Joinability::NotJoinable
+		} else {
+			Joinability::Unknown
+		}
+	}
+
+	/// Check if every rule has a linear left-hand side.
+	pub fn is_left_linear(&self) -> bool {
+		self.rules().iter().all(|rule| rule.lhs().variable_count() == rule.lhs().variables().count())
+	}
+}
+
+impl<F: Clone + PartialEq, X: Index> RewriteSystem<F, X> {
+	/// Every critical pair of the system, including the overlaps of a rule with itself (except at the root).
+	pub fn critical_pairs(&self) -> Vec<CriticalPair<F, X>> {
+		let mut pairs = Vec::new();
+		for (i, outer) in self.rules().iter().enumerate() {
+			for (j, inner) in self.rules().iter().enumerate() {
+				let (a, next) = reindex_from(&[outer.lhs(), outer.rhs()], X::ZERO);
+				let (b, _) = reindex_from(&[inner.lhs(), inner.rhs()], next);
+				pairs.extend(overlaps((i, &a[0], &a[1]), (j, &b[0], &b[1])))
+			}
+		}
+
+		pairs
+	}
+
+	/// Check the local confluence of the system, by trying to join every critical pair within `limit` steps.
+	///
+	/// Returns [`Joinability::Joinable`] if every critical pair is joinable,
+	/// [`Joinability::NotJoinable`] if a critical pair is proved not joinable,
+	/// and [`Joinability::Unknown`] otherwise.
+	pub fn local_confluence(&self, limit: usize) -> Joinability {
+		let mut result = Joinability::Joinable;
+		for pair in self.critical_pairs() {
+			match self.joinability(&pair.left, &pair.right, limit) {
+				Joinability::Joinable => (),
+				Joinability::NotJoinable => return Joinability::NotJoinable,
+				Joinability::Unknown => result = Joinability::Unknown
+			}
+		}
+
+		result
+	}
+
+	/// Check if no left-hand side overlaps another one (or itself, except at the root).
+	pub fn is_non_overlapping(&self) -> bool {
+		self.critical_pairs().is_empty()
+	}
+
+	/// Check if the system is left-linear and non-overlapping.
+	///
+	/// Orthogonal systems are confluent.
+	pub fn is_orthogonal(&self) -> bool {
+		self.is_left_linear() && self.is_non_overlapping()
+	}
+}
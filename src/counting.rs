@@ -0,0 +1,97 @@
+use crate::Ranked;
+
+/// Count the terms of a given size over a ranked alphabet.
+///
+/// Counts are exact `u128` values, or `None` when they overflow.
+/// The tables are extended on demand and kept for later queries.
+/// Symbols appearing several times in the alphabet are counted several times.
+pub struct Counter<F> {
+	alphabet: Vec<F>,
+
+	/// `terms[n]` is the number of terms of size `n`.
+	terms: Vec<Option<u128>>,
+
+	/// `forests[k][m]` is the number of sequences of `k` terms of total size `m`.
+	forests: Vec<Vec<Option<u128>>>
+}
+
+/// Checked addition, where `None` stands for an overflowed value.
+pub(crate) fn add(a: Option<u128>, b: Option<u128>) -> Option<u128> {
+	a?.checked_add(b?)
+}
+
+/// Checked multiplication, where `None` stands for an overflowed value.
+///
+/// Anything multiplied by 0 is 0, even an overflowed value.
+pub(crate) fn mul(a: Option<u128>, b: Option<u128>) -> Option<u128> {
+	match (a, b) {
+		(Some(0), _) | (_, Some(0)) => Some(0),
+		(Some(a), Some(b)) => a.checked_mul(b),
+		_ => None
+	}
+}
+
+impl<F: Ranked> Counter<F> {
+	pub fn new(alphabet: Vec<F>) -> Counter<F> {
+		let max_arity = alphabet.iter().map(Ranked::arity).max().unwrap_or(0);
+		Counter {
+			alphabet,
+			terms: vec![Some(0)],
+			forests: vec![Vec::new(); max_arity + 1]
+		}
+	}
+
+	pub fn alphabet(&self) -> &[F] {
+		&self.alphabet
+	}
+
+	/// Maximum arity of the alphabet symbols.
+	pub fn max_arity(&self) -> usize {
+		self.forests.len() - 1
+	}
+
+	/// Extend the tables up to the given size.
+	fn extend(&mut self, size: usize) {
+		for n in self.terms.len()..=size {
+			let m = n - 1;
+			for k in 0..self.forests.len() {
+				let count = if k == 0 {
+					Some(if m == 0 { 1 } else { 0 })
+				} else {
+					let mut count = Some(0);
+					for s in 1..=m {
+						count = add(count, mul(self.terms[s], self.forests[k-1][m - s]))
+					}
+					count
+				};
+
+				self.forests[k].push(count)
+			}
+
+			let mut count = Some(0);
+			for f in &self.alphabet {
+				count = add(count, self.forests[f.arity()][m])
+			}
+			self.terms.push(count)
+		}
+	}
+
+	/// Number of terms of the given size, or `None` if it does not fit in a `u128`.
+	pub fn count(&mut self, size: usize) -> Option<u128> {
+		self.extend(size);
+		self.terms[size]
+	}
+
+	/// Number of terms of the given size, saturated to `u128::MAX`.
+	pub fn count_saturating(&mut self, size: usize) -> u128 {
+		self.count(size).unwrap_or(u128::MAX)
+	}
+
+	/// Number of sequences of `k` terms of total size `size`, or `None` if it does not fit in a `u128`.
+	///
+	/// `k` must not exceed the maximum arity of the alphabet.
+	pub fn count_sequences(&mut self, k: usize, size: usize) -> Option<u128> {
+		self.extend(size + 1);
+		self.forests[k][size]
+	}
+}
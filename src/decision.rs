@@ -0,0 +1,234 @@
+use crate::{Term, Pattern, PatternKind, Substitution};
+
+/// A node of a compiled [`DecisionTree`].
+///
+/// The sub-terms inspected by the matching are stored in slots, numbered in the order they are reached:
+/// the input term is in slot 0, and when a case of a switch is taken,
+/// the sub-terms of the tested term are pushed in the following slots.
+enum Decision<F, X> {
+	/// No more arm can match.
+	Fail,
+
+	/// Arms matching if their non-linear variables are bound to equal sub-terms,
+	/// with the slot of every variable occurrence.
+	///
+	/// The following arms are then matched by `next`.
+	Accept {
+		arms: Vec<(usize, Vec<(X, usize)>)>,
+		next: Box<Decision<F, X>>
+	},
+
+	/// Test the symbol of the term in the given slot.
+	///
+	/// The default branch is taken if no case matches.
+	Switch {
+		slot: usize,
+		cases: Vec<(F, usize, Decision<F, X>)>,
+		default: Box<Decision<F, X>>
+	}
+}
+
+/// Row of the pattern matrix: arm index, columns, and variable bindings found so far.
+///
+/// A `None` column matches anything without binding.
+type Row<'a, F, X> = (usize, Vec<Option<&'a Pattern<F, X>>>, Vec<(X, usize)>);
+
+/// Decision tree matching a term against an ordered list of patterns.
+///
+/// The tree is compiled once from the patterns (the arms),
+/// and inspects each position of the term at most once,
+/// instead of trying every pattern in sequence.
+/// Non-linear patterns are checked when they are reached, by comparing the sub-terms bound to the same variable.
+///
+/// In the worst case, the size of the tree is exponential in the size of the patterns.
+pub struct DecisionTree<F, X> {
+	root: Decision<F, X>,
+	arms: usize
+}
+
+/// Compile the pattern matrix, where `slots` gives the slot of each column.
+fn compile<F: Clone + PartialEq, X: Clone>(rows: Vec<Row<'_, F, X>>, slots: Vec<usize>, next_slot: usize) -> Decision<F, X> {
+	let is_wildcard = |p: &Option<&Pattern<F, X>>| !matches!(p.map(Pattern::kind), Some(PatternKind::Cons(_, _)));
+
+	let first = match rows.first() {
+		Some(first) => first,
+		None => return Decision::Fail
+	};
+
+	let column = match first.1.iter().position(|p| !is_wildcard(p)) {
+		Some(column) => column,
+		None => {
+			// Accept every leading row made of wildcards.
+			let n = rows.iter().take_while(|row| row.1.iter().all(is_wildcard)).count();
+			let mut rows = rows.into_iter();
+			let arms = rows.by_ref().take(n).map(|(arm, columns, mut bindings)| {
+				for (p, slot) in columns.iter().zip(slots.iter()) {
+					if let Some(PatternKind::Var(x)) = p.map(Pattern::kind) {
+						bindings.push((x.clone(), *slot))
+					}
+				}
+
+				(arm, bindings)
+			}).collect();
+
+			return Decision::Accept {
+				arms,
+				next: Box::new(compile(rows.collect(), slots, next_slot))
+			}
+		}
+	};
+
+	let slot = slots[column];
+
+	// Symbols (with their arity) tested in this column, in order of appearance.
+	let mut symbols: Vec<(&F, usize)> = Vec::new();
+	for (_, columns, _) in &rows {
+		if let Some(PatternKind::Cons(f, subs)) = columns[column].map(Pattern::kind) {
+			if !symbols.iter().any(|(g, k)| *g == f && *k == subs.len()) {
+				symbols.push((f, subs.len()))
+			}
+		}
+	}
+
+	let cases = symbols.into_iter().map(|(f, arity)| {
+		let mut case_slots = slots[..column].to_vec();
+		case_slots.extend(next_slot..(next_slot + arity));
+		case_slots.extend_from_slice(&slots[(column+1)..]);
+
+		let case_rows = rows.iter().filter_map(|(arm, columns, bindings)| {
+			let mut bindings = bindings.clone();
+			let mut case_columns = columns[..column].to_vec();
+			match columns[column].map(Pattern::kind) {
+				Some(PatternKind::Cons(g, subs)) => {
+					if g != f || subs.len() != arity {
+						return None
+					}
+
+					case_columns.extend(subs.iter().map(Some))
+				},
+				Some(PatternKind::Var(x)) => {
+					bindings.push((x.clone(), slot));
+					case_columns.extend((0..arity).map(|_| None))
+				},
+				None => case_columns.extend((0..arity).map(|_| None))
+			}
+
+			case_columns.extend_from_slice(&columns[(column+1)..]);
+			Some((*arm, case_columns, bindings))
+		}).collect();
+
+		(f.clone(), arity, compile(case_rows, case_slots, next_slot + arity))
+	}).collect();
+
+	let mut default_slots = slots.clone();
+	default_slots.remove(column);
+	let default_rows = rows.into_iter().filter_map(|(arm, mut columns, mut bindings)| {
+		match columns.remove(column).map(Pattern::kind) {
+			Some(PatternKind::Cons(_, _)) => return None,
+			Some(PatternKind::Var(x)) => bindings.push((x.clone(), slot)),
+			None => ()
+		}
+
+		Some((arm, columns, bindings))
+	}).collect();
+
+	Decision::Switch {
+		slot,
+		cases,
+		default: Box::new(compile(default_rows, default_slots, next_slot))
+	}
+}
+
+impl<F: Clone + PartialEq, X: Clone + PartialEq> DecisionTree<F, X> {
+	/// Compile the given list of patterns.
+	pub fn new(patterns: &[Pattern<F, X>]) -> DecisionTree<F, X> {
+		let rows = patterns.iter().enumerate().map(|(i, p)| (i, vec![Some(p)], Vec::new())).collect();
+		DecisionTree {
+			root: compile(rows, vec![0], 1),
+			arms: patterns.len()
+		}
+	}
+
+	/// Number of arms.
+	pub fn len(&self) -> usize {
+		self.arms
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.arms == 0
+	}
+
+	/// Run the tree on the given term, calling `f` on each matching arm (in order) until it returns `false`.
+	fn run<C: FnMut(usize, Substitution<F, X>) -> bool>(&self, term: &Term<F>, mut f: C) {
+		let mut slots = vec![term];
+		let mut node = &self.root;
+		loop {
+			match node {
+				Decision::Fail => break,
+				Decision::Accept { arms, next } => {
+					for (arm, bindings) in arms {
+						if let Some(sigma) = bind(bindings, &slots) {
+							if !f(*arm, sigma) {
+								return
+							}
+						}
+					}
+
+					node = next
+				},
+				Decision::Switch { slot, cases, default } => {
+					let t = slots[*slot];
+					match cases.iter().find(|(g, arity, _)| g == t.symbol() && *arity == t.sub_terms().len()) {
+						Some((_, _, case)) => {
+							slots.extend(t.sub_terms().iter());
+							node = case
+						},
+						None => node = default
+					}
+				}
+			}
+		}
+	}
+
+	/// Find the first arm matching the term, with its substitution.
+	pub fn first_match(&self, term: &Term<F>) -> Option<(usize, Substitution<F, X>)> {
+		let mut result = None;
+		self.run(term, |arm, sigma| {
+			result = Some((arm, sigma));
+			false
+		});
+
+		result
+	}
+
+	/// Find every arm matching the term, in order, with their substitution.
+	pub fn all_matches(&self, term: &Term<F>) -> Vec<(usize, Substitution<F, X>)> {
+		let mut results = Vec::new();
+		self.run(term, |arm, sigma| {
+			results.push((arm, sigma));
+			true
+		});
+
+		results
+	}
+}
+
+/// Build the substitution of an accepted arm, checking that non-linear variables are bound to equal sub-terms.
+fn bind<F: Clone + PartialEq, X: Clone + PartialEq>(bindings: &[(X, usize)], slots: &[&Term<F>]) -> Option<Substitution<F, X>> {
+	let mut sigma = Substitution::new();
+	for (x, slot) in bindings {
+		let t = slots[*slot];
+		match sigma.get(x) {
+			Some(p) => {
+				if !p.eq_like(t) {
+					return None
+				}
+			},
+			None => {
+				sigma.bind(x.clone(), Pattern::from_like(t));
+			}
+		}
+	}
+
+	Some(sigma)
+}
@@ -0,0 +1,258 @@
+use std::hash::Hash;
+use std::collections::HashMap;
+use crate::{Term, Pattern, PatternKind, Substitution};
+
+/// Discrimination tree key: a symbol with its arity, or a variable.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Key<F> {
+	Symbol(F, usize),
+	Variable
+}
+
+impl<F> Key<F> {
+	/// Number of sub-terms following the key in the pre-order sequence.
+	pub fn arity(&self) -> usize {
+		match self {
+			Key::Symbol(_, arity) => *arity,
+			Key::Variable => 0
+		}
+	}
+}
+
+/// Pre-order sequence of keys of a pattern.
+pub fn keys<F: Clone, X>(pattern: &Pattern<F, X>) -> impl Iterator<Item = Key<F>> + '_ {
+	pattern.pre_order().map(|p| match p.kind() {
+		PatternKind::Cons(f, subs) => Key::Symbol(f.clone(), subs.len()),
+		PatternKind::Var(_) => Key::Variable
+	})
+}
+
+/// A retrieved entry of a [`DiscriminationTree`].
+pub struct Retrieved<'a, F, X, V> {
+	pub pattern: &'a Pattern<F, X>,
+	pub value: &'a V,
+
+	/// Matching substitution.
+	///
+	/// For [`DiscriminationTree::generalizations`], it maps the stored pattern to the query term.
+	/// For [`DiscriminationTree::instances`], it maps the query pattern to the stored pattern.
+	pub substitution: Substitution<F, X>
+}
+
+struct Node<F, X, V> {
+	children: HashMap<Key<F>, Node<F, X, V>>,
+
+	/// Patterns ending at this node, with their value.
+	///
+	/// Patterns with the same keys differ only by their variables.
+	entries: Vec<(Pattern<F, X>, V)>
+}
+
+impl<F: Eq + Hash, X, V> Node<F, X, V> {
+	fn new() -> Node<F, X, V> {
+		Node {
+			children: HashMap::new(),
+			entries: Vec::new()
+		}
+	}
+
+	fn is_empty(&self) -> bool {
+		self.children.is_empty() && self.entries.is_empty()
+	}
+}
+
+/// Discrimination tree, indexing patterns by their pre-order sequence of symbols,
+/// where every variable is replaced by the same [`Key::Variable`].
+///
+/// It maps patterns to values, and efficiently retrieves the patterns
+/// matching a term (generalizations), or the patterns that are instances of a given pattern.
+/// The tree only filters candidates: non-linear patterns are then checked by matching.
+pub struct DiscriminationTree<F, X, V> {
+	root: Node<F, X, V>,
+	len: usize
+}
+
+impl<F: Clone + Eq + Hash, X: Clone + PartialEq, V> DiscriminationTree<F, X, V> {
+	pub fn new() -> DiscriminationTree<F, X, V> {
+		DiscriminationTree {
+			root: Node::new(),
+			len: 0
+		}
+	}
+
+	/// Number of stored patterns.
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Insert a pattern with its value.
+	///
+	/// If the pattern was already in the tree, its value is replaced and the previous one is returned.
+	pub fn insert(&mut self, pattern: Pattern<F, X>, value: V) -> Option<V> {
+		let mut node = &mut self.root;
+		for key in keys(&pattern) {
+			node = node.children.entry(key).or_insert_with(Node::new)
+		}
+
+		for entry in &mut node.entries {
+			if entry.0 == pattern {
+				return Some(std::mem::replace(&mut entry.1, value))
+			}
+		}
+
+		node.entries.push((pattern, value));
+		self.len += 1;
+		None
+	}
+
+	/// Get the value associated to the given pattern.
+	pub fn get(&self, pattern: &Pattern<F, X>) -> Option<&V> {
+		let mut node = &self.root;
+		for key in keys(pattern) {
+			node = node.children.get(&key)?
+		}
+
+		node.entries.iter().find(|(p, _)| p == pattern).map(|(_, v)| v)
+	}
+
+	/// Remove a pattern from the tree, and return its value.
+	///
+	/// Branches that are no longer used are removed.
+	pub fn remove(&mut self, pattern: &Pattern<F, X>) -> Option<V> {
+		let keys: Vec<Key<F>> = keys(pattern).collect();
+		let value = Self::remove_from(&mut self.root, &keys, pattern);
+		if value.is_some() {
+			self.len -= 1
+		}
+
+		value
+	}
+
+	fn remove_from(node: &mut Node<F, X, V>, keys: &[Key<F>], pattern: &Pattern<F, X>) -> Option<V> {
+		match keys.split_first() {
+			None => {
+				let i = node.entries.iter().position(|(p, _)| p == pattern)?;
+				Some(node.entries.remove(i).1)
+			},
+			Some((key, rest)) => {
+				let child = node.children.get_mut(key)?;
+				let value = Self::remove_from(child, rest, pattern);
+				if child.is_empty() {
+					node.children.remove(key);
+				}
+
+				value
+			}
+		}
+	}
+
+	/// Iterate over the stored patterns and their values.
+	pub fn iter(&self) -> impl Iterator<Item = (&Pattern<F, X>, &V)> {
+		let mut stack = vec![&self.root];
+		let mut entries = Vec::new();
+		while let Some(node) = stack.pop() {
+			entries.extend(node.entries.iter().map(|(p, v)| (p, v)));
+			stack.extend(node.children.values())
+		}
+
+		entries.into_iter()
+	}
+
+	/// Candidate patterns that may match the given term.
+	///
+	/// Every pattern matching the term is a candidate, but non-linear candidates may not match.
+	pub fn generalization_candidates(&self, term: &Term<F>) -> Vec<(&Pattern<F, X>, &V)> {
+		let subjects: Vec<&Term<F>> = term.pre_order().collect();
+		let mut candidates = Vec::new();
+		let mut stack = vec![(&self.root, 0)];
+		while let Some((node, i)) = stack.pop() {
+			match subjects.get(i) {
+				None => candidates.extend(node.entries.iter().map(|(p, v)| (p, v))),
+				Some(t) => {
+					// a variable skips the whole sub-term.
+					if let Some(child) = node.children.get(&Key::Variable) {
+						stack.push((child, i + t.size()))
+					}
+
+					let key = Key::Symbol(t.symbol().clone(), t.sub_terms().len());
+					if let Some(child) = node.children.get(&key) {
+						stack.push((child, i + 1))
+					}
+				}
+			}
+		}
+
+		candidates
+	}
+
+	/// Stored patterns matching the given term, with the matching substitution.
+	pub fn generalizations(&self, term: &Term<F>) -> Vec<Retrieved<'_, F, X, V>> {
+		self.generalization_candidates(term).into_iter().filter_map(|(pattern, value)| {
+			pattern.matches(term).map(|substitution| Retrieved { pattern, value, substitution })
+		}).collect()
+	}
+
+	/// Candidate patterns that may be instances of the given pattern.
+	///
+	/// Variables of the stored patterns are treated as constants.
+	/// Every instance is a candidate, but candidates may not be instances if the query is non-linear.
+	pub fn instance_candidates(&self, pattern: &Pattern<F, X>) -> Vec<(&Pattern<F, X>, &V)> {
+		let queries: Vec<&Pattern<F, X>> = pattern.pre_order().collect();
+		let mut candidates = Vec::new();
+		// node, index in the query, number of stored sub-terms to skip.
+		let mut stack = vec![(&self.root, 0, 0)];
+		while let Some((node, i, skip)) = stack.pop() {
+			if skip > 0 {
+				for (key, child) in &node.children {
+					stack.push((child, i, skip - 1 + key.arity()))
+				}
+				continue
+			}
+
+			match queries.get(i) {
+				None => candidates.extend(node.entries.iter().map(|(p, v)| (p, v))),
+				Some(q) => match q.kind() {
+					PatternKind::Var(_) => stack.push((node, i + q.size(), 1)),
+					PatternKind::Cons(f, subs) => {
+						let key = Key::Symbol(f.clone(), subs.len());
+						if let Some(child) = node.children.get(&key) {
+							stack.push((child, i + 1, 0))
+						}
+					}
+				}
+			}
+		}
+
+		candidates
+	}
+
+	/// Stored patterns that are instances of the given pattern, with the matching substitution.
+	///
+	/// Variables of the stored patterns are treated as constants.
+	pub fn instances(&self, pattern: &Pattern<F, X>) -> Vec<Retrieved<'_, F, X, V>> {
+		self.instance_candidates(pattern).into_iter().filter_map(|(stored, value)| {
+			pattern.matches_like(stored).map(|substitution| Retrieved { pattern: stored, value, substitution })
+		}).collect()
+	}
+}
+
+impl<F: Clone + Eq + Hash, X: Clone + PartialEq, V> Default for DiscriminationTree<F, X, V> {
+	fn default() -> DiscriminationTree<F, X, V> {
+		DiscriminationTree::new()
+	}
+}
+
+impl<F: Clone + Eq + Hash, X: Clone + PartialEq, V> std::iter::FromIterator<(Pattern<F, X>, V)> for DiscriminationTree<F, X, V> {
+	fn from_iter<I: IntoIterator<Item = (Pattern<F, X>, V)>>(iter: I) -> DiscriminationTree<F, X, V> {
+		let mut tree = DiscriminationTree::new();
+		for (pattern, value) in iter {
+			tree.insert(pattern, value);
+		}
+
+		tree
+	}
+}
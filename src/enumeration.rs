@@ -0,0 +1,332 @@
+use crate::{Term, Ranked};
+use crate::counting::{Counter, mul, add};
+
+/// Lazy enumeration of every term over a ranked alphabet, by increasing size.
+///
+/// Terms of the same size are ordered by head symbol (following the alphabet order),
+/// then by the size of the first sub-term, then by the first sub-term itself, then by the rest of the sub-terms.
+/// This is the order of [`Counter::rank`] and [`Counter::unrank`].
+///
+/// Every term of a given size is kept in memory to build the greater terms,
+/// so that sub-terms are shared between the enumerated terms.
+/// The alphabet should not contain the same symbol twice, otherwise terms are enumerated more than once.
+pub struct Enumerator<F> {
+	alphabet: Vec<F>,
+
+	/// `levels[n]` contains every term of size `n`.
+	levels: Vec<Vec<Term<F>>>,
+
+	/// Index of the next term in the last level.
+	index: usize,
+
+	/// The set of terms is finite (all terms have size 1).
+	finite: bool
+}
+
+/// Check if the alphabet only generates terms of size 1 (or no term at all).
+fn is_finite<F: Ranked>(alphabet: &[F]) -> bool {
+	alphabet.iter().all(|f| f.arity() == 0) || alphabet.iter().all(|f| f.arity() > 0)
+}
+
+impl<F: Clone + Ranked> Enumerator<F> {
+	pub fn new(alphabet: &[F]) -> Enumerator<F> {
+		Enumerator {
+			alphabet: alphabet.to_vec(),
+			levels: vec![Vec::new()],
+			index: 0,
+			finite: is_finite(alphabet)
+		}
+	}
+
+	/// Size of the terms currently enumerated.
+	pub fn size(&self) -> usize {
+		self.levels.len() - 1
+	}
+
+	/// Every sequence of `k` terms of total size `m`.
+	fn sequences(&self, k: usize, m: usize) -> Vec<Vec<Term<F>>> {
+		if k == 0 {
+			return if m == 0 { vec![Vec::new()] } else { Vec::new() }
+		}
+
+		let mut result = Vec::new();
+		for s in 1..=m {
+			if self.levels[s].is_empty() {
+				continue
+			}
+
+			let rests = self.sequences(k - 1, m - s);
+			for t in &self.levels[s] {
+				for rest in &rests {
+					let mut seq = Vec::with_capacity(k);
+					seq.push(t.clone());
+					seq.extend(rest.iter().cloned());
+					result.push(seq)
+				}
+			}
+		}
+
+		result
+	}
+
+	/// Build the next level.
+	fn grow(&mut self) {
+		let m = self.size();
+		let mut level = Vec::new();
+		for f in &self.alphabet {
+			for subs in self.sequences(f.arity(), m) {
+				level.push(Term::new(f.clone(), subs))
+			}
+		}
+
+		self.levels.push(level);
+		self.index = 0
+	}
+}
+
+impl<F: Clone + Ranked> Iterator for Enumerator<F> {
+	type Item = Term<F>;
+
+	fn next(&mut self) -> Option<Term<F>> {
+		loop {
+			if let Some(term) = self.levels.last().unwrap().get(self.index) {
+				self.index += 1;
+				return Some(term.clone())
+			}
+
+			if self.finite && self.size() >= 1 {
+				return None
+			}
+
+			self.grow()
+		}
+	}
+}
+
+/// Lazy enumeration of every term over a ranked alphabet, by increasing depth.
+///
+/// Terms of the same depth are ordered by head symbol (following the alphabet order),
+/// then by sub-terms, lexicographically.
+/// Every enumerated term is kept in memory to build the deeper terms.
+pub struct DepthEnumerator<F> {
+	alphabet: Vec<F>,
+
+	/// Every term of depth less than the current depth.
+	terms: Vec<Term<F>>,
+
+	/// Index in `terms` of the first term of depth `depth - 1`.
+	boundary: usize,
+
+	/// The current level.
+	level: Vec<Term<F>>,
+
+	/// Index of the next term in the current level.
+	index: usize,
+
+	/// Depth of the current level, or `None` before the first level.
+	depth: Option<u64>
+}
+
+impl<F: Clone + Ranked> DepthEnumerator<F> {
+	pub fn new(alphabet: &[F]) -> DepthEnumerator<F> {
+		DepthEnumerator {
+			alphabet: alphabet.to_vec(),
+			terms: Vec::new(),
+			boundary: 0,
+			level: Vec::new(),
+			index: 0,
+			depth: None
+		}
+	}
+
+	/// Build the next level.
+	fn grow(&mut self) {
+		let mut level = Vec::new();
+		match self.depth {
+			None => {
+				for f in &self.alphabet {
+					if f.arity() == 0 {
+						level.push(Term::new(f.clone(), Vec::new()))
+					}
+				}
+
+				self.depth = Some(0)
+			},
+			Some(depth) => {
+				self.boundary = self.terms.len();
+				self.terms.append(&mut self.level);
+				let n = self.terms.len();
+				for f in &self.alphabet {
+					let arity = f.arity();
+					if arity == 0 || n == 0 {
+						continue
+					}
+
+					// Enumerate every tuple of indexes with at least one term of depth `depth`.
+					let mut indexes = vec![0; arity];
+					loop {
+						if indexes.iter().any(|i| *i >= self.boundary) {
+							let subs = indexes.iter().map(|i| self.terms[*i].clone()).collect();
+							level.push(Term::new(f.clone(), subs))
+						}
+
+						match indexes.iter().rposition(|i| *i + 1 < n) {
+							Some(j) => {
+								indexes[j] += 1;
+								for i in &mut indexes[(j+1)..] {
+									*i = 0
+								}
+							},
+							None => break
+						}
+					}
+				}
+
+				self.depth = Some(depth + 1)
+			}
+		}
+
+		self.level = level;
+		self.index = 0
+	}
+}
+
+impl<F: Clone + Ranked> Iterator for DepthEnumerator<F> {
+	type Item = Term<F>;
+
+	fn next(&mut self) -> Option<Term<F>> {
+		if let Some(term) = self.level.get(self.index) {
+			self.index += 1;
+			return Some(term.clone())
+		}
+
+		if self.depth.is_some() && self.level.is_empty() {
+			return None
+		}
+
+		self.grow();
+		if let Some(term) = self.level.get(self.index) {
+			self.index += 1;
+			Some(term.clone())
+		} else {
+			None
+		}
+	}
+}
+
+impl<F: Clone + PartialEq + Ranked> Counter<F> {
+	/// Number of terms of size less than `size`, or `None` if it does not fit in a `u128`.
+	fn offset(&mut self, size: usize) -> Option<u128> {
+		let mut offset = Some(0);
+		for s in 1..size {
+			offset = add(offset, self.count(s))
+		}
+
+		offset
+	}
+
+	/// Index of the given term in the enumeration order of [`Enumerator`].
+	///
+	/// Returns `None` if a symbol of the term is not in the alphabet,
+	/// or if the index does not fit in a `u128`.
+	pub fn rank(&mut self, term: &Term<F>) -> Option<u128> {
+		let local = self.local_rank(term)?;
+		self.offset(term.size())?.checked_add(local)
+	}
+
+	/// Index of the given term among the terms of the same size.
+	fn local_rank(&mut self, term: &Term<F>) -> Option<u128> {
+		let m = term.size() - 1;
+		let i = self.alphabet().iter().position(|f| f == term.symbol() && f.arity() == term.sub_terms().len())?;
+		let mut rank = Some(0);
+		for j in 0..i {
+			let k = self.alphabet()[j].arity();
+			rank = add(rank, self.count_sequences(k, m))
+		}
+
+		add(rank, self.sequence_rank(term.sub_terms(), m))
+	}
+
+	/// Index of the given sequence among the sequences of the same length and total size `m`.
+	fn sequence_rank(&mut self, terms: &[Term<F>], m: usize) -> Option<u128> {
+		match terms.split_first() {
+			None => Some(0),
+			Some((first, rest)) => {
+				let k = rest.len();
+				let s = first.size();
+				let mut rank = Some(0);
+				for s in 1..s {
+					rank = add(rank, mul(self.count(s), self.count_sequences(k, m - s)))
+				}
+
+				let block = self.count_sequences(k, m - s);
+				rank = add(rank, mul(self.local_rank(first), block));
+				add(rank, self.sequence_rank(rest, m - s))
+			}
+		}
+	}
+
+	/// Term of the given index in the enumeration order of [`Enumerator`].
+	///
+	/// Returns `None` if there are not enough terms, or if the index is not reachable without overflow.
+	pub fn unrank(&mut self, mut index: u128) -> Option<Term<F>> {
+		if is_finite(self.alphabet()) {
+			return if index < self.count(1)? { self.local_unrank(1, index) } else { None }
+		}
+
+		let mut size = 1;
+		loop {
+			let count = self.count(size)?;
+			if index < count {
+				return self.local_unrank(size, index)
+			}
+
+			index -= count;
+			size += 1
+		}
+	}
+
+	/// Term of the given index among the terms of size `size`.
+	fn local_unrank(&mut self, size: usize, mut index: u128) -> Option<Term<F>> {
+		let m = size - 1;
+		for i in 0..self.alphabet().len() {
+			let k = self.alphabet()[i].arity();
+			let count = self.count_sequences(k, m)?;
+			if index < count {
+				let f = self.alphabet()[i].clone();
+				let subs = self.sequence_unrank(k, m, index)?;
+				return Some(Term::new(f, subs))
+			}
+
+			index -= count
+		}
+
+		None
+	}
+
+	/// Sequence of `k` terms of total size `m` of the given index.
+	fn sequence_unrank(&mut self, k: usize, m: usize, mut index: u128) -> Option<Vec<Term<F>>> {
+		if k == 0 {
+			return Some(Vec::new())
+		}
+
+		for s in 1..=m {
+			if self.count(s) == Some(0) {
+				continue
+			}
+
+			let block = self.count_sequences(k - 1, m - s)?;
+			let count = mul(self.count(s), Some(block))?;
+			if index < count {
+				let first = self.local_unrank(s, index / block)?;
+				let mut seq = vec![first];
+				seq.extend(self.sequence_unrank(k - 1, m - s, index % block)?);
+				return Some(seq)
+			}
+
+			index -= count
+		}
+
+		None
+	}
+}
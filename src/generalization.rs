@@ -0,0 +1,114 @@
+use crate::{Pattern, PatternLike, PatternLikeKind, Substitution};
+use crate::variable::Spawnable;
+
+/// Result of the anti-unification of several terms or patterns.
+pub struct Generalization<F, X> {
+	/// Least general generalization.
+	pub pattern: Pattern<F, X>,
+
+	/// Substitutions recovering each input from the generalization.
+	pub substitutions: Vec<Substitution<F, X>>
+}
+
+/// Anti-unification state.
+///
+/// Remembers the disagreement tuples already replaced by a variable,
+/// so that the same variable is reused for repeated disagreements.
+struct Generalizer<'n, F, X: Spawnable> {
+	namespace: &'n X::Namespace,
+	disagreements: Vec<(Vec<Pattern<F, X>>, X)>
+}
+
+impl<'n, F: Clone + PartialEq, X: Spawnable> Generalizer<'n, F, X> {
+	fn generalize<T: PatternLike<F, X>>(&mut self, items: &[&T]) -> Pattern<F, X> {
+		let first = items[0];
+		match first.kind() {
+			PatternLikeKind::Cons(f, subs) => {
+				let same_head = items[1..].iter().all(|item| match item.kind() {
+					PatternLikeKind::Cons(g, other_subs) => f == g && subs.len() == other_subs.len(),
+					_ => false
+				});
+
+				if same_head {
+					let mut sub_patterns = Vec::with_capacity(subs.len());
+					for i in 0..subs.len() {
+						let sub_items: Vec<&T> = items.iter().map(|item| match item.kind() {
+							PatternLikeKind::Cons(_, subs) => &subs[i],
+							_ => unreachable!()
+						}).collect();
+						sub_patterns.push(self.generalize(&sub_items))
+					}
+
+					return Pattern::cons(f.clone(), sub_patterns)
+				}
+			},
+			PatternLikeKind::Var(x) => {
+				let same_var = items[1..].iter().all(|item| match item.kind() {
+					PatternLikeKind::Var(y) => x == y,
+					_ => false
+				});
+
+				if same_var {
+					return Pattern::var(x.clone())
+				}
+			}
+		}
+
+		self.disagreement(items)
+	}
+
+	fn disagreement<T: PatternLike<F, X>>(&mut self, items: &[&T]) -> Pattern<F, X> {
+		for (tuple, x) in self.disagreements.iter() {
+			if tuple.iter().zip(items.iter()).all(|(p, item)| p.eq_like(*item)) {
+				return Pattern::var(x.clone())
+			}
+		}
+
+		let x = X::spawn(self.namespace);
+		let tuple = items.iter().map(|item| Pattern::from_like(*item)).collect();
+		self.disagreements.push((tuple, x.clone()));
+		Pattern::var(x)
+	}
+}
+
+impl<F: Clone + PartialEq, X: Spawnable> Pattern<F, X> {
+	/// Compute the least general generalization of two terms or patterns.
+	///
+	/// Returns the generalization along with the substitutions recovering each input.
+	/// Fresh variables are spawned in the given namespace for each distinct disagreement pair.
+	pub fn generalize<T: PatternLike<F, X>>(a: &T, b: &T, namespace: &X::Namespace) -> (Pattern<F, X>, Substitution<F, X>, Substitution<F, X>) {
+		let Generalization { pattern, mut substitutions } = Self::generalize_all(&[a, b], namespace).unwrap();
+		let sigma_b = substitutions.pop().unwrap();
+		let sigma_a = substitutions.pop().unwrap();
+		(pattern, sigma_a, sigma_b)
+	}
+
+	/// Compute the least general generalization of any number of terms or patterns.
+	///
+	/// The substitutions recovering each input are given in the same order as the inputs.
+	/// Returns `None` if no input is given.
+	pub fn generalize_all<T: PatternLike<F, X>>(items: &[&T], namespace: &X::Namespace) -> Option<Generalization<F, X>> {
+		if items.is_empty() {
+			return None
+		}
+
+		let mut generalizer = Generalizer {
+			namespace,
+			disagreements: Vec::new()
+		};
+
+		let pattern = generalizer.generalize(items);
+
+		let mut substitutions: Vec<Substitution<F, X>> = items.iter().map(|_| Substitution::new()).collect();
+		for (tuple, x) in generalizer.disagreements {
+			for (sigma, p) in substitutions.iter_mut().zip(tuple) {
+				sigma.bind(x.clone(), p);
+			}
+		}
+
+		Some(Generalization {
+			pattern,
+			substitutions
+		})
+	}
+}
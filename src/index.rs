@@ -74,3 +74,26 @@ impl<F: Clone, X: Index> Pattern<F, X> {
         })
     }
 }
+
+/// Reindex the variables of the given patterns to consecutive indexes starting from `start`,
+/// in the order of their first occurrence.
+///
+/// A variable occurring in several of the patterns gets the same index in each of them,
+/// so variants are reindexed to the same patterns.
+/// Returns the reindexed patterns, and the index following the last one used:
+/// reindexing other patterns from there renames them apart from these ones.
+pub(crate) fn reindex_from<F: Clone, X: Clone + PartialEq, Y: Index>(patterns: &[&Pattern<F, X>], start: Y) -> (Vec<Pattern<F, Y>>, Y) {
+    let mut indexes: Vec<(&X, Y)> = Vec::new();
+    let mut next = start;
+    for pattern in patterns {
+        for x in pattern.variables() {
+            if !indexes.iter().any(|(y, _)| *y == x) {
+                indexes.push((x, next));
+                next = next.next()
+            }
+        }
+    }
+
+    let map = |x: &X| Pattern::var(indexes.iter().find(|(y, _)| *y == x).unwrap().1);
+    (patterns.iter().map(|pattern| pattern.map_variables(&map)).collect(), next)
+}
@@ -0,0 +1,110 @@
+use std::sync::{Arc, Weak};
+use std::hash::{Hash, Hasher};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use crate::Term;
+
+/// Hash-consing table for terms.
+///
+/// Every term built by the interner is maximally shared:
+/// two interned terms are equal if and only if they have the same symbol and share the same list of sub-terms.
+/// Equality between interned terms is then decided without visiting the sub-terms,
+/// and their hash is computed once at construction.
+///
+/// The table only keeps weak references to the lists of sub-terms,
+/// so terms are freed as soon as they are no longer used outside of the interner.
+pub struct TermInterner<F> {
+	empty: Arc<Vec<Term<F>>>,
+	table: HashMap<u64, Vec<Weak<Vec<Term<F>>>>>
+}
+
+impl<F: Clone + Eq + Hash> TermInterner<F> {
+	pub fn new() -> TermInterner<F> {
+		TermInterner {
+			empty: Arc::new(Vec::new()),
+			table: HashMap::new()
+		}
+	}
+
+	/// Build an interned term from interned sub-terms.
+	///
+	/// Every sub-term must have been built by this interner,
+	/// otherwise the maximal sharing is not guaranteed.
+	pub fn make(&mut self, f: F, subs: Vec<Term<F>>) -> Term<F> {
+		let subs = if subs.is_empty() {
+			self.empty.clone()
+		} else {
+			self.intern_sub_terms(subs)
+		};
+
+		let term = Term::from_arc(f, subs);
+
+		// Compute the hash now, while the sub-terms hashes are known.
+		term.hash(&mut DefaultHasher::new());
+		term
+	}
+
+	/// Build the interned version of any term.
+	pub fn intern(&mut self, term: &Term<F>) -> Term<F> {
+		let mut stack: Vec<Term<F>> = Vec::new();
+		for t in term.post_order() {
+			let arity = t.sub_terms().len();
+			let subs = stack.split_off(stack.len() - arity);
+			stack.push(self.make(t.symbol().clone(), subs))
+		}
+
+		stack.pop().unwrap()
+	}
+
+	/// Number of (possibly dead) lists of sub-terms in the table.
+	pub fn len(&self) -> usize {
+		self.table.values().map(Vec::len).sum()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Remove every entry of the table that is no longer used.
+	pub fn collect_garbage(&mut self) {
+		self.table.retain(|_, bucket| {
+			bucket.retain(|subs| subs.strong_count() > 0);
+			!bucket.is_empty()
+		})
+	}
+
+	fn intern_sub_terms(&mut self, subs: Vec<Term<F>>) -> Arc<Vec<Term<F>>> {
+		let mut hasher = DefaultHasher::new();
+		subs.hash(&mut hasher);
+		let key = hasher.finish();
+
+		let bucket = self.table.entry(key).or_default();
+		bucket.retain(|candidate| candidate.strong_count() > 0);
+		for candidate in bucket.iter() {
+			if let Some(candidate) = candidate.upgrade() {
+				if candidate.len() == subs.len() && candidate.iter().zip(subs.iter()).all(|(a, b)| a.ptr_eq(b)) {
+					return candidate
+				}
+			}
+		}
+
+		let subs = Arc::new(subs);
+		bucket.push(Arc::downgrade(&subs));
+		subs
+	}
+}
+
+impl<F: Clone + Eq + Hash> Default for TermInterner<F> {
+	fn default() -> TermInterner<F> {
+		TermInterner::new()
+	}
+}
+
+impl<F> Term<F> {
+	/// Check if the two terms have the same symbol and share the same list of sub-terms.
+	///
+	/// This implies equality, and is equivalent to it for terms built by the same [`TermInterner`].
+	pub fn ptr_eq(&self, other: &Term<F>) -> bool where F: PartialEq {
+		Arc::ptr_eq(&self.subs, &other.subs) && self.f == other.f
+	}
+}
@@ -9,11 +9,54 @@ use std::sync::atomic::{self, AtomicU64};
 use std::fmt;
 
 pub mod pattern;
+pub mod substitution;
+pub mod unification;
+pub mod generalization;
+pub mod position;
+pub mod traversal;
+pub mod interner;
+pub mod parse;
+pub mod rewriting;
+pub mod signature;
+pub mod sort;
+pub mod order;
+pub mod simplification;
+pub mod random;
+pub mod counting;
+pub mod enumeration;
+pub mod discrimination;
+pub mod substitution_tree;
+pub mod decision;
+pub mod completion;
+pub mod confluence;
+pub mod narrowing;
+pub mod theory;
 pub mod variable;
 pub mod macros;
 mod index;
 
 pub use pattern::{Pattern, PatternKind, PatternLike, PatternLikeKind};
+pub use substitution::Substitution;
+pub use unification::UnificationError;
+pub use generalization::Generalization;
+pub use position::Position;
+pub use interner::TermInterner;
+pub use parse::ParseError;
+pub use rewriting::{Rule, RewriteSystem, Strategy};
+pub use signature::{Signature, SignatureError};
+pub use sort::{SortedSignature, SortError};
+pub use order::{TermOrder, Ordered};
+pub use simplification::{Comparison, ReductionOrder};
+pub use random::{Generator, UniformGenerator, RandomError};
+pub use counting::Counter;
+pub use enumeration::{Enumerator, DepthEnumerator};
+pub use discrimination::DiscriminationTree;
+pub use substitution_tree::SubstitutionTree;
+pub use decision::DecisionTree;
+pub use completion::CompletionError;
+pub use confluence::{CriticalPair, Joinability};
+pub use narrowing::{NarrowingStep, NarrowingStrategy};
+pub use theory::{Theory, Axioms};
 pub use variable::Var;
 pub use index::*;
 
@@ -27,6 +70,8 @@ pub trait Ranked {
 pub struct Term<F> {
     f: F,
     subs: Arc<Vec<Self>>,
+    size: usize,
+    depth: u64,
     hash: AtomicU64
 }
 
@@ -55,20 +100,32 @@ impl<F: fmt::Display> fmt::Display for Term<F> {
 }
 
 impl<F> Term<F> {
-    pub fn new(f: F, subs: Vec<Self>) -> Self where F: Clone {
+    /// Build a term, computing its size and depth once and for all.
+    pub(crate) fn from_arc(f: F, subs: Arc<Vec<Self>>) -> Self {
+        let mut size = 1;
+        let mut depth = 0;
+        for sub in subs.iter() {
+            size += sub.size;
+            if sub.depth + 1 > depth {
+                depth = sub.depth + 1
+            }
+        }
+
         Term {
-            f: f,
-            subs: Arc::new(subs),
+            f,
+            subs,
+            size,
+            depth,
             hash: AtomicU64::new(0)
         }
     }
 
+    pub fn new(f: F, subs: Vec<Self>) -> Self where F: Clone {
+        Self::from_arc(f, Arc::new(subs))
+    }
+
     pub fn from_slice(f: F, subs: &[Self]) -> Self where F: Clone {
-        Term {
-            f: f,
-            subs: Arc::new(subs.iter().map(|p| p.clone()).collect()),
-            hash: AtomicU64::new(0)
-        }
+        Self::from_arc(f, Arc::new(subs.to_vec()))
     }
 
     pub fn symbol(&self) -> &F {
@@ -94,57 +151,27 @@ impl<F> Term<F> {
     //     &mut self.subs
     // }
 
+    /// Depth of the term (0 for constants).
     pub fn depth(&self) -> u64 {
-        let mut depth = 0;
-        for sub in self.subs.iter() {
-            let d = sub.depth() + 1;
-            if d > depth {
-                depth = d
-            }
-        }
-
-        depth
+        self.depth
     }
 
-    fn random_with_zeros(zero_alphabet: &[F], alphabet: &[F], max_depth: u64) -> Term<F> where F: Clone + Ranked {
-        let i: usize = rand::random();
-        let (f, arity, next_depth) = if max_depth == 0 {
-            let f: &F = &zero_alphabet[i%zero_alphabet.len()];
-            (f.clone(), 0, 0)
-        } else {
-            let f: &F = &alphabet[i%alphabet.len()];
-            (f.clone(), f.arity(), max_depth-1)
-        };
-
-        let mut subs = Vec::with_capacity(arity);
-        for _ in 0..arity {
-            subs.push(Self::random_with_zeros(zero_alphabet, alphabet, next_depth))
-        }
-
-        Term {
-            f: f,
-            subs: Arc::new(subs),
-            hash: AtomicU64::new(0)
-        }
+    /// Number of symbol occurrences in the term.
+    pub fn size(&self) -> usize {
+        self.size
     }
 
     /// Generate a random term with the given alphabet.
     /// The alphabet must contain at least one constant (of arity 0), otherwise it will panic.
+    ///
+    /// See [`random::Generator`] for reproducible generation.
     pub fn random(alphabet: &[F], max_depth: u64) -> Term<F> where F: Clone + Ranked {
-        let mut zeros = Vec::with_capacity(alphabet.len());
-        for f in alphabet.iter() {
-            if f.arity() == 0 {
-                zeros.push(f.clone())
-            }
-        }
-
-        assert!(!zeros.is_empty());
-        Self::random_with_zeros(&zeros, alphabet, max_depth)
+        random::Generator::new(alphabet).max_depth(max_depth).max_size(usize::MAX).term(&mut rand::thread_rng()).expect("no constant in the alphabet")
     }
 }
 
 impl<F, X> PatternLike<F, X> for Term<F> {
-    fn kind(&self) -> PatternLikeKind<F, X, Self> {
+    fn kind(&self) -> PatternLikeKind<'_, F, X, Self> {
         PatternLikeKind::Cons(&self.f, &self.subs)
     }
 }
@@ -154,6 +181,8 @@ impl<F: Clone> Clone for Term<F> {
         Term {
             f: self.f.clone(),
             subs: self.subs.clone(),
+            size: self.size,
+            depth: self.depth,
             hash: AtomicU64::new(self.hash.load(atomic::Ordering::Relaxed)),
         }
     }
@@ -161,7 +190,18 @@ impl<F: Clone> Clone for Term<F> {
 
 impl<F: PartialEq> PartialEq for Term<F> {
     fn eq(&self, other: &Term<F>) -> bool {
-        self.f == other.f && self.subs == other.subs
+        if Arc::ptr_eq(&self.subs, &other.subs) {
+            return self.f == other.f
+        }
+
+        // terms with different (computed) hashes are different.
+        let h = self.hash.load(atomic::Ordering::Relaxed);
+        let other_h = other.hash.load(atomic::Ordering::Relaxed);
+        if h > 1 && other_h > 1 && h != other_h {
+            return false
+        }
+
+        self.size == other.size && self.f == other.f && self.subs == other.subs
     }
 }
 
@@ -0,0 +1,210 @@
+use std::collections::VecDeque;
+use crate::{Pattern, PatternKind, Position, Substitution, Index, reindex_from};
+use crate::rewriting::RewriteSystem;
+
+/// A narrowing step: the sub-pattern at `position` is unified with the left-hand side of a rule,
+/// and replaced by its right-hand side.
+pub struct NarrowingStep<F, X> {
+	/// Position of the narrowed sub-pattern.
+	pub position: Position,
+
+	/// Index of the rule used.
+	pub rule: usize,
+
+	/// Most general unifier of the sub-pattern and the (renamed) left-hand side.
+	pub substitution: Substitution<F, X>,
+
+	/// Narrowed pattern.
+	pub result: Pattern<F, X>
+}
+
+/// Positions considered by the narrowing search.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum NarrowingStrategy {
+	/// Basic narrowing: only narrow at positions that were not introduced by a previous unifier.
+	///
+	/// It is complete for convergent systems.
+	Basic,
+
+	/// Innermost basic narrowing: only narrow at the innermost narrowable basic positions.
+	///
+	/// It is complete for convergent constructor systems where every defined function is total.
+	Innermost
+}
+
+/// The index following every variable of the given patterns.
+fn fresh<'a, F: 'a, X: Index + 'a, I: IntoIterator<Item = &'a Pattern<F, X>>>(patterns: I) -> X {
+	patterns.into_iter().flat_map(|p| p.variable_occurrences()).max().map(|x| x.next()).unwrap_or(X::ZERO)
+}
+
+impl<F: Clone + PartialEq, X: Index> RewriteSystem<F, X> {
+	/// Every narrowing step at the given position, with the rules renamed to variables starting from `next`.
+	fn steps_at(&self, pattern: &Pattern<F, X>, position: &Position, next: X) -> Vec<NarrowingStep<F, X>> {
+		let mut steps = Vec::new();
+		let sub = match pattern.at(position) {
+			Some(sub) => sub,
+			None => return steps
+		};
+
+		if let PatternKind::Var(_) = sub.kind() {
+			return steps
+		}
+
+		for (i, rule) in self.rules().iter().enumerate() {
+			let (mut renamed, _) = reindex_from(&[rule.lhs(), rule.rhs()], next);
+			let rhs = renamed.pop().unwrap();
+			if let Ok(sigma) = sub.unify(&renamed[0]) {
+				let result = sigma.apply(&pattern.replace_at(position, rhs).unwrap());
+				steps.push(NarrowingStep {
+					position: position.clone(),
+					rule: i,
+					substitution: sigma,
+					result
+				})
+			}
+		}
+
+		steps
+	}
+
+	/// Every narrowing step of the pattern, at any non-variable position.
+	///
+	/// Rules are renamed apart from the pattern, using indexes greater than every variable of the pattern.
+	pub fn narrowing_steps(&self, pattern: &Pattern<F, X>) -> Vec<NarrowingStep<F, X>> {
+		let next = fresh(Some(pattern));
+		pattern.positions().iter().flat_map(|position| self.steps_at(pattern, position, next)).collect()
+	}
+
+	/// Solve the equation `lhs = rhs` modulo the rewrite system, by narrowing.
+	///
+	/// Returns a lazy iterator over the answer substitutions (restricted to the variables of the equation),
+	/// found by a breadth-first search of at most `max_depth` narrowing steps.
+	/// When the system is convergent, this is a complete E-unification procedure
+	/// for the equational theory of the rules, up to the depth bound.
+	pub fn solve(&self, lhs: &Pattern<F, X>, rhs: &Pattern<F, X>, strategy: NarrowingStrategy, max_depth: usize) -> Solutions<'_, F, X> {
+		let mut variables: Vec<X> = Vec::new();
+		for x in lhs.variables().chain(rhs.variables()) {
+			if !variables.contains(x) {
+				variables.push(*x)
+			}
+		}
+
+		let basic = [lhs, rhs].iter().enumerate().flat_map(|(side, p)| {
+			p.pre_order().with_positions().filter(|(_, sub)| sub.symbol().is_some()).map(move |(position, _)| (side, position))
+		}).collect();
+
+		let mut queue = VecDeque::new();
+		queue.push_back(Goal {
+			sides: [lhs.clone(), rhs.clone()],
+			basic,
+			substitution: Substitution::new(),
+			depth: 0,
+			next: fresh(vec![lhs, rhs])
+		});
+
+		Solutions {
+			system: self,
+			strategy,
+			max_depth,
+			variables,
+			queue,
+			answers: Vec::new()
+		}
+	}
+}
+
+/// A goal of the narrowing search.
+struct Goal<F, X> {
+	sides: [Pattern<F, X>; 2],
+
+	/// Basic positions, with the side they belong to.
+	basic: Vec<(usize, Position)>,
+
+	/// Composition of the unifiers used so far.
+	substitution: Substitution<F, X>,
+
+	depth: usize,
+
+	/// Next fresh variable.
+	next: X
+}
+
+/// Lazy iterator over the solutions of an equation modulo a rewrite system.
+///
+/// See [`RewriteSystem::solve`].
+pub struct Solutions<'a, F, X> {
+	system: &'a RewriteSystem<F, X>,
+	strategy: NarrowingStrategy,
+	max_depth: usize,
+
+	/// Variables of the equation.
+	variables: Vec<X>,
+
+	queue: VecDeque<Goal<F, X>>,
+
+	/// Answers already returned.
+	answers: Vec<Substitution<F, X>>
+}
+
+impl<'a, F: Clone + PartialEq, X: Index> Solutions<'a, F, X> {
+	/// Push every goal obtained by one narrowing step.
+	fn expand(&mut self, goal: &Goal<F, X>) {
+		let mut steps = Vec::new();
+		for (side, position) in &goal.basic {
+			for step in self.system.steps_at(&goal.sides[*side], position, goal.next) {
+				steps.push((*side, step))
+			}
+		}
+
+		if self.strategy == NarrowingStrategy::Innermost {
+			let narrowable: Vec<(usize, Position)> = steps.iter().map(|(side, step)| (*side, step.position.clone())).collect();
+			steps.retain(|(side, step)| {
+				!narrowable.iter().any(|(other_side, q)| other_side == side && step.position.is_strict_prefix_of(q))
+			})
+		}
+
+		for (side, step) in steps {
+			let rule = &self.system.rules()[step.rule];
+			let (_, next) = reindex_from(&[rule.lhs(), rule.rhs()], goal.next);
+
+			let mut basic: Vec<(usize, Position)> = goal.basic.iter().filter(|(s, q)| *s != side || !step.position.is_prefix_of(q)).cloned().collect();
+			for (q, sub) in rule.rhs().pre_order().with_positions() {
+				if sub.symbol().is_some() {
+					basic.push((side, step.position.concat(&q)))
+				}
+			}
+
+			let other = step.substitution.apply(&goal.sides[1 - side]);
+			let sides = if side == 0 { [step.result, other] } else { [other, step.result] };
+			self.queue.push_back(Goal {
+				sides,
+				basic,
+				substitution: goal.substitution.compose(&step.substitution),
+				depth: goal.depth + 1,
+				next
+			})
+		}
+	}
+}
+
+impl<'a, F: Clone + PartialEq, X: Index> Iterator for Solutions<'a, F, X> {
+	type Item = Substitution<F, X>;
+
+	fn next(&mut self) -> Option<Substitution<F, X>> {
+		while let Some(goal) = self.queue.pop_front() {
+			if goal.depth < self.max_depth {
+				self.expand(&goal)
+			}
+
+			if let Ok(mgu) = goal.sides[0].unify(&goal.sides[1]) {
+				let answer = goal.substitution.compose(&mgu).restrict(self.variables.iter());
+				if !self.answers.contains(&answer) {
+					self.answers.push(answer.clone());
+					return Some(answer)
+				}
+			}
+		}
+
+		None
+	}
+}
@@ -0,0 +1,232 @@
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::fmt;
+use crate::{Term, Pattern, PatternKind};
+
+/// A total order on terms or patterns.
+///
+/// Orders are types, so that they can be attached to values with the [`Ordered`] wrapper,
+/// and used in ordered collections such as `BTreeSet`.
+pub trait TermOrder<T> {
+	fn compare(a: &T, b: &T) -> Ordering;
+
+	/// Sort the given slice with this order.
+	fn sort(slice: &mut [T]) {
+		slice.sort_by(Self::compare)
+	}
+}
+
+/// How variables compare to constructors in pattern orders.
+pub trait VariablePolicy {
+	/// Ordering of a variable compared to a constructor pattern.
+	const VARIABLE_CONS: Ordering;
+}
+
+/// Variables are smaller than constructor patterns.
+pub struct VariablesFirst;
+
+/// Variables are greater than constructor patterns.
+pub struct VariablesLast;
+
+impl VariablePolicy for VariablesFirst {
+	const VARIABLE_CONS: Ordering = Ordering::Less;
+}
+
+impl VariablePolicy for VariablesLast {
+	const VARIABLE_CONS: Ordering = Ordering::Greater;
+}
+
+/// Lexicographic order: compare the symbols, then the sub-terms from left to right,
+/// and then the number of sub-terms.
+///
+/// When the common sub-terms are equal, the term with fewer sub-terms is smaller,
+/// so `f(a)` is smaller than `f(a, b)`, and `g(f(a), c)` is smaller than `g(f(a, b), b)`.
+/// This is not the order on the pre-order sequences of symbols.
+pub struct Lexicographic<P = VariablesFirst>(PhantomData<P>);
+
+/// Compare the size first, then use the lexicographic order (shortlex).
+pub struct SizeLexicographic<P = VariablesFirst>(PhantomData<P>);
+
+/// Compare the depth first, then use the lexicographic order.
+pub struct DepthLexicographic<P = VariablesFirst>(PhantomData<P>);
+
+/// The order of the `Ord` implementation of [`Term`]:
+/// compare the depth, then the symbol, then the arity, then recursively the sub-terms from left to right.
+pub struct Natural<P = VariablesFirst>(PhantomData<P>);
+
+fn lexicographic_terms<F: Ord>(a: &Term<F>, b: &Term<F>) -> Ordering {
+	a.symbol().cmp(b.symbol()).then_with(|| {
+		for (sub_a, sub_b) in a.sub_terms().iter().zip(b.sub_terms().iter()) {
+			match lexicographic_terms(sub_a, sub_b) {
+				Ordering::Equal => (),
+				ord => return ord
+			}
+		}
+
+		a.sub_terms().len().cmp(&b.sub_terms().len())
+	})
+}
+
+fn lexicographic_patterns<F: Ord, X: Ord, P: VariablePolicy>(a: &Pattern<F, X>, b: &Pattern<F, X>) -> Ordering {
+	match (a.kind(), b.kind()) {
+		(PatternKind::Var(x), PatternKind::Var(y)) => x.cmp(y),
+		(PatternKind::Var(_), PatternKind::Cons(_, _)) => P::VARIABLE_CONS,
+		(PatternKind::Cons(_, _), PatternKind::Var(_)) => P::VARIABLE_CONS.reverse(),
+		(PatternKind::Cons(f, subs_a), PatternKind::Cons(g, subs_b)) => {
+			f.cmp(g).then_with(|| {
+				for (sub_a, sub_b) in subs_a.iter().zip(subs_b.iter()) {
+					match lexicographic_patterns::<F, X, P>(sub_a, sub_b) {
+						Ordering::Equal => (),
+						ord => return ord
+					}
+				}
+
+				subs_a.len().cmp(&subs_b.len())
+			})
+		}
+	}
+}
+
+fn natural_patterns<F: Ord, X: Ord, P: VariablePolicy>(a: &Pattern<F, X>, b: &Pattern<F, X>) -> Ordering {
+	a.depth().cmp(&b.depth()).then_with(|| {
+		match (a.kind(), b.kind()) {
+			(PatternKind::Var(x), PatternKind::Var(y)) => x.cmp(y),
+			(PatternKind::Var(_), PatternKind::Cons(_, _)) => P::VARIABLE_CONS,
+			(PatternKind::Cons(_, _), PatternKind::Var(_)) => P::VARIABLE_CONS.reverse(),
+			(PatternKind::Cons(f, subs_a), PatternKind::Cons(g, subs_b)) => {
+				f.cmp(g).then_with(|| subs_a.len().cmp(&subs_b.len())).then_with(|| {
+					for (sub_a, sub_b) in subs_a.iter().zip(subs_b.iter()) {
+						match natural_patterns::<F, X, P>(sub_a, sub_b) {
+							Ordering::Equal => (),
+							ord => return ord
+						}
+					}
+
+					Ordering::Equal
+				})
+			}
+		}
+	})
+}
+
+impl<F: Ord, P> TermOrder<Term<F>> for Lexicographic<P> {
+	fn compare(a: &Term<F>, b: &Term<F>) -> Ordering {
+		lexicographic_terms(a, b)
+	}
+}
+
+impl<F: Ord, X: Ord, P: VariablePolicy> TermOrder<Pattern<F, X>> for Lexicographic<P> {
+	fn compare(a: &Pattern<F, X>, b: &Pattern<F, X>) -> Ordering {
+		lexicographic_patterns::<F, X, P>(a, b)
+	}
+}
+
+impl<F: Ord, P> TermOrder<Term<F>> for SizeLexicographic<P> {
+	fn compare(a: &Term<F>, b: &Term<F>) -> Ordering {
+		a.size().cmp(&b.size()).then_with(|| lexicographic_terms(a, b))
+	}
+}
+
+impl<F: Ord, X: Ord, P: VariablePolicy> TermOrder<Pattern<F, X>> for SizeLexicographic<P> {
+	fn compare(a: &Pattern<F, X>, b: &Pattern<F, X>) -> Ordering {
+		a.size().cmp(&b.size()).then_with(|| lexicographic_patterns::<F, X, P>(a, b))
+	}
+}
+
+impl<F: Ord, P> TermOrder<Term<F>> for DepthLexicographic<P> {
+	fn compare(a: &Term<F>, b: &Term<F>) -> Ordering {
+		a.depth().cmp(&b.depth()).then_with(|| lexicographic_terms(a, b))
+	}
+}
+
+impl<F: Ord, X: Ord, P: VariablePolicy> TermOrder<Pattern<F, X>> for DepthLexicographic<P> {
+	fn compare(a: &Pattern<F, X>, b: &Pattern<F, X>) -> Ordering {
+		a.depth().cmp(&b.depth()).then_with(|| lexicographic_patterns::<F, X, P>(a, b))
+	}
+}
+
+impl<F: Ord, P> TermOrder<Term<F>> for Natural<P> {
+	fn compare(a: &Term<F>, b: &Term<F>) -> Ordering {
+		a.cmp(b)
+	}
+}
+
+impl<F: Ord, X: Ord, P: VariablePolicy> TermOrder<Pattern<F, X>> for Natural<P> {
+	fn compare(a: &Pattern<F, X>, b: &Pattern<F, X>) -> Ordering {
+		natural_patterns::<F, X, P>(a, b)
+	}
+}
+
+/// A value ordered with the given order.
+///
+/// This can be used to store terms or patterns in a `BTreeSet` or `BTreeMap` with a custom order.
+pub struct Ordered<T, O> {
+	value: T,
+	order: PhantomData<O>
+}
+
+impl<T, O> Ordered<T, O> {
+	pub fn new(value: T) -> Ordered<T, O> {
+		Ordered {
+			value,
+			order: PhantomData
+		}
+	}
+
+	pub fn into_inner(self) -> T {
+		self.value
+	}
+}
+
+impl<T, O> Deref for Ordered<T, O> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.value
+	}
+}
+
+impl<T, O> From<T> for Ordered<T, O> {
+	fn from(value: T) -> Ordered<T, O> {
+		Ordered::new(value)
+	}
+}
+
+impl<T: Clone, O> Clone for Ordered<T, O> {
+	fn clone(&self) -> Ordered<T, O> {
+		Ordered::new(self.value.clone())
+	}
+}
+
+impl<T: fmt::Debug, O> fmt::Debug for Ordered<T, O> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.value.fmt(f)
+	}
+}
+
+impl<T: fmt::Display, O> fmt::Display for Ordered<T, O> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.value.fmt(f)
+	}
+}
+
+impl<T, O: TermOrder<T>> PartialEq for Ordered<T, O> {
+	fn eq(&self, other: &Ordered<T, O>) -> bool {
+		O::compare(&self.value, &other.value) == Ordering::Equal
+	}
+}
+
+impl<T, O: TermOrder<T>> Eq for Ordered<T, O> {}
+
+impl<T, O: TermOrder<T>> PartialOrd for Ordered<T, O> {
+	fn partial_cmp(&self, other: &Ordered<T, O>) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<T, O: TermOrder<T>> Ord for Ordered<T, O> {
+	fn cmp(&self, other: &Ordered<T, O>) -> Ordering {
+		O::compare(&self.value, &other.value)
+	}
+}
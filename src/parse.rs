@@ -0,0 +1,345 @@
+use std::fmt;
+use std::str::FromStr;
+use std::convert::Infallible;
+use crate::{Term, Pattern, PatternKind};
+
+/// Describes how identifiers are parsed into symbols and variables.
+pub trait Syntax<F, X> {
+	/// Parse a symbol identifier.
+	fn symbol(&self, id: &str) -> Option<F>;
+
+	/// Parse a variable identifier.
+	///
+	/// `prefixed` is true when the identifier was prefixed by `?` (the prefix is not part of `id`),
+	/// in which case it must be a variable.
+	/// Otherwise, returning `None` means that the identifier is a symbol.
+	fn variable(&self, id: &str, prefixed: bool) -> Option<X>;
+}
+
+/// Syntax where variables are identifiers prefixed by `?`, such as `f(?x, a)`.
+pub struct Prefixed<S, V> {
+	pub symbol: S,
+	pub variable: V
+}
+
+impl<F, X, S: Fn(&str) -> Option<F>, V: Fn(&str) -> Option<X>> Syntax<F, X> for Prefixed<S, V> {
+	fn symbol(&self, id: &str) -> Option<F> {
+		(self.symbol)(id)
+	}
+
+	fn variable(&self, id: &str, prefixed: bool) -> Option<X> {
+		if prefixed {
+			(self.variable)(id)
+		} else {
+			None
+		}
+	}
+}
+
+/// Syntax where variables are identifiers starting with an uppercase letter, such as `f(X, a)`.
+///
+/// Identifiers prefixed by `?` are also variables.
+pub struct Uppercase<S, V> {
+	pub symbol: S,
+	pub variable: V
+}
+
+impl<F, X, S: Fn(&str) -> Option<F>, V: Fn(&str) -> Option<X>> Syntax<F, X> for Uppercase<S, V> {
+	fn symbol(&self, id: &str) -> Option<F> {
+		(self.symbol)(id)
+	}
+
+	fn variable(&self, id: &str, prefixed: bool) -> Option<X> {
+		if prefixed || id.chars().next().map(char::is_uppercase).unwrap_or(false) {
+			(self.variable)(id)
+		} else {
+			None
+		}
+	}
+}
+
+/// Syntax without variables.
+struct Ground<S>(S);
+
+impl<F, S: Fn(&str) -> Option<F>> Syntax<F, Infallible> for Ground<S> {
+	fn symbol(&self, id: &str) -> Option<F> {
+		(self.0)(id)
+	}
+
+	fn variable(&self, _id: &str, _prefixed: bool) -> Option<Infallible> {
+		None
+	}
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ParseErrorKind {
+	/// The input ended too early.
+	UnexpectedEnd,
+
+	/// Unexpected character.
+	Unexpected(char),
+
+	/// The identifier is not a valid symbol.
+	InvalidSymbol(String),
+
+	/// The identifier is not a valid variable.
+	InvalidVariable(String),
+
+	/// A variable is applied to arguments.
+	AppliedVariable(String)
+}
+
+/// Parse error, located by its byte offset in the input.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseError {
+	pub offset: usize,
+	pub kind: ParseErrorKind
+}
+
+impl fmt::Display for ParseErrorKind {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+			ParseErrorKind::Unexpected(c) => write!(f, "unexpected character `{}`", c),
+			ParseErrorKind::InvalidSymbol(id) => write!(f, "invalid symbol `{}`", id),
+			ParseErrorKind::InvalidVariable(id) => write!(f, "invalid variable `{}`", id),
+			ParseErrorKind::AppliedVariable(id) => write!(f, "variable `{}` cannot have arguments", id)
+		}
+	}
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} at offset {}", self.kind, self.offset)
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+/// Output of the parser.
+trait Node<F, X>: Sized {
+	fn cons(f: F, subs: Vec<Self>) -> Self;
+
+	fn var(x: X) -> Self;
+}
+
+impl<F: Clone> Node<F, Infallible> for Term<F> {
+	fn cons(f: F, subs: Vec<Self>) -> Self {
+		Term::new(f, subs)
+	}
+
+	fn var(x: Infallible) -> Self {
+		match x {}
+	}
+}
+
+impl<F: Clone, X: Clone> Node<F, X> for Pattern<F, X> {
+	fn cons(f: F, subs: Vec<Self>) -> Self {
+		Pattern::cons(f, subs)
+	}
+
+	fn var(x: X) -> Self {
+		Pattern::var(x)
+	}
+}
+
+struct Parser<'s, 'y, Y> {
+	input: &'s str,
+	offset: usize,
+	syntax: &'y Y
+}
+
+impl<'s, 'y, Y> Parser<'s, 'y, Y> {
+	fn peek(&self) -> Option<char> {
+		self.input[self.offset..].chars().next()
+	}
+
+	fn skip_whitespaces(&mut self) {
+		while let Some(c) = self.peek() {
+			if c.is_whitespace() {
+				self.offset += c.len_utf8()
+			} else {
+				break
+			}
+		}
+	}
+
+	fn error<T>(&self, kind: ParseErrorKind) -> Result<T, ParseError> {
+		Err(ParseError {
+			offset: self.offset,
+			kind
+		})
+	}
+
+	fn unexpected<T>(&self) -> Result<T, ParseError> {
+		match self.peek() {
+			Some(c) => self.error(ParseErrorKind::Unexpected(c)),
+			None => self.error(ParseErrorKind::UnexpectedEnd)
+		}
+	}
+
+	fn identifier(&mut self) -> Result<&'s str, ParseError> {
+		let start = self.offset;
+		while let Some(c) = self.peek() {
+			if c.is_whitespace() || c == '(' || c == ')' || c == ',' || c == '?' {
+				break
+			}
+			self.offset += c.len_utf8()
+		}
+
+		if self.offset == start {
+			self.unexpected()
+		} else {
+			Ok(&self.input[start..self.offset])
+		}
+	}
+
+	fn parse<F, X, T: Node<F, X>>(&mut self) -> Result<T, ParseError> where Y: Syntax<F, X> {
+		self.skip_whitespaces();
+		let start = self.offset;
+		let prefixed = self.peek() == Some('?');
+		if prefixed {
+			self.offset += 1;
+		}
+
+		let id = self.identifier()?;
+		let var = self.syntax.variable(id, prefixed);
+		if prefixed && var.is_none() {
+			return Err(ParseError {
+				offset: start,
+				kind: ParseErrorKind::InvalidVariable(id.to_string())
+			})
+		}
+
+		self.skip_whitespaces();
+		let mut subs = Vec::new();
+		if self.peek() == Some('(') {
+			if var.is_some() {
+				return Err(ParseError {
+					offset: start,
+					kind: ParseErrorKind::AppliedVariable(id.to_string())
+				})
+			}
+
+			self.offset += 1;
+			self.skip_whitespaces();
+			if self.peek() == Some(')') {
+				self.offset += 1;
+			} else {
+				loop {
+					subs.push(self.parse()?);
+					self.skip_whitespaces();
+					match self.peek() {
+						Some(',') => self.offset += 1,
+						Some(')') => {
+							self.offset += 1;
+							break
+						},
+						_ => return self.unexpected()
+					}
+				}
+			}
+		}
+
+		match var {
+			Some(x) => Ok(T::var(x)),
+			None => match self.syntax.symbol(id) {
+				Some(f) => Ok(T::cons(f, subs)),
+				None => Err(ParseError {
+					offset: start,
+					kind: ParseErrorKind::InvalidSymbol(id.to_string())
+				})
+			}
+		}
+	}
+
+	fn parse_all<F, X, T: Node<F, X>>(&mut self) -> Result<T, ParseError> where Y: Syntax<F, X> {
+		let result = self.parse()?;
+		self.skip_whitespaces();
+		if self.offset < self.input.len() {
+			self.unexpected()
+		} else {
+			Ok(result)
+		}
+	}
+}
+
+impl<F: Clone> Term<F> {
+	/// Parse a term written as `f(a, g(b))`, using the given function to parse symbols.
+	pub fn parse_with<S: Fn(&str) -> Option<F>>(input: &str, symbol: S) -> Result<Term<F>, ParseError> {
+		Parser {
+			input,
+			offset: 0,
+			syntax: &Ground(symbol)
+		}.parse_all()
+	}
+}
+
+impl<F: Clone, X: Clone> Pattern<F, X> {
+	/// Parse a pattern written as `f(?x, g(b))`, using the given syntax.
+	pub fn parse_with<Y: Syntax<F, X>>(input: &str, syntax: &Y) -> Result<Pattern<F, X>, ParseError> {
+		Parser {
+			input,
+			offset: 0,
+			syntax
+		}.parse_all()
+	}
+
+	/// Display the pattern with variables prefixed by `?`, such as `f(?x, g(b))`.
+	///
+	/// Unlike the `Display` implementation of `Pattern`,
+	/// the output can be parsed back with `Pattern::from_str`.
+	pub fn display_with_marks(&self) -> WithMarks<'_, F, X> {
+		WithMarks(self)
+	}
+}
+
+/// Displays a pattern with variables prefixed by `?`.
+///
+/// See [`Pattern::display_with_marks`].
+pub struct WithMarks<'a, F, X>(&'a Pattern<F, X>);
+
+impl<'a, F: Clone + fmt::Display, X: Clone + fmt::Display> fmt::Display for WithMarks<'a, F, X> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.0.kind() {
+			PatternKind::Cons(g, subs) => {
+				g.fmt(f)?;
+				match subs.split_first() {
+					Some((head, tail)) => {
+						write!(f, "(")?;
+						head.display_with_marks().fmt(f)?;
+						for e in tail.iter() {
+							write!(f, ", ")?;
+							e.display_with_marks().fmt(f)?;
+						}
+						write!(f, ")")
+					},
+					None => Ok(())
+				}
+			},
+			PatternKind::Var(x) => write!(f, "?{}", x)
+		}
+	}
+}
+
+impl<F: Clone + FromStr> FromStr for Term<F> {
+	type Err = ParseError;
+
+	fn from_str(s: &str) -> Result<Term<F>, ParseError> {
+		Term::parse_with(s, |id| id.parse().ok())
+	}
+}
+
+impl<F: Clone + FromStr, X: Clone + FromStr> FromStr for Pattern<F, X> {
+	type Err = ParseError;
+
+	/// Parse a pattern where variables are prefixed by `?`.
+	///
+	/// This is the syntax used by [`Pattern::display_with_marks`].
+	fn from_str(s: &str) -> Result<Pattern<F, X>, ParseError> {
+		Pattern::parse_with(s, &Prefixed {
+			symbol: |id: &str| id.parse().ok(),
+			variable: |id: &str| id.parse().ok()
+		})
+	}
+}
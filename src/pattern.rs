@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use std::collections::hash_map::DefaultHasher;
 use std::sync::atomic::{self, AtomicU64};
 use std::fmt;
-use crate::Term;
+use crate::{Term, Substitution};
 
 // pub trait Meta<F>: Clone + Eq + Sized + fmt::Debug {
 //	 /// Gives the sub-patterns needed to recognize the given symbol.
@@ -22,7 +22,7 @@ use crate::Term;
 
 /// Any object that can act like a pattern. Such as a term.
 pub trait PatternLike<F, X>: Sized {
-	fn kind(&self) -> PatternLikeKind<F, X, Self>;
+	fn kind(&self) -> PatternLikeKind<'_, F, X, Self>;
 }
 
 pub enum PatternLikeKind<'a, F, X, T: PatternLike<F, X>> {
@@ -36,14 +36,17 @@ pub trait Spawnable: Sized {
 
 pub struct Pattern<F, X> {
 	kind: PatternKind<F, X>,
+	size: usize,
+	depth: u64,
+	variables: usize,
 	hash: AtomicU64
 }
 
 impl<F, X> PatternLike<F, X> for Pattern<F, X> {
-	fn kind(&self) -> PatternLikeKind<F, X, Self> {
+	fn kind(&self) -> PatternLikeKind<'_, F, X, Self> {
 		match &self.kind {
-			PatternKind::Cons(f, subs) => PatternLikeKind::Cons(&f, &subs),
-			PatternKind::Var(x) => PatternLikeKind::Var(&x)
+			PatternKind::Cons(f, subs) => PatternLikeKind::Cons(f, subs),
+			PatternKind::Var(x) => PatternLikeKind::Var(x)
 		}
 	}
 }
@@ -87,6 +90,34 @@ pub enum PatternKind<F, X> {
 }
 
 impl<F, X> Pattern<F, X> {
+	/// Build a pattern, computing its size, depth and number of variables once and for all.
+	fn from_kind(kind: PatternKind<F, X>) -> Self {
+		let (size, depth, variables) = match &kind {
+			PatternKind::Cons(_, subs) => {
+				let mut size = 1;
+				let mut depth = 0;
+				let mut variables = 0;
+				for sub in subs.iter() {
+					size += sub.size;
+					variables += sub.variables;
+					if sub.depth + 1 > depth {
+						depth = sub.depth + 1
+					}
+				}
+				(size, depth, variables)
+			},
+			PatternKind::Var(_) => (1, 0, 1)
+		};
+
+		Pattern {
+			kind,
+			size,
+			depth,
+			variables,
+			hash: AtomicU64::new(0)
+		}
+	}
+
 	pub fn kind(&self) -> &PatternKind<F, X> {
 		&self.kind
 	}
@@ -96,24 +127,35 @@ impl<F, X> Pattern<F, X> {
 	}
 
 	pub fn cons(f: F, subs: Vec<Self>) -> Self where F: Clone, X: Clone {
-		Pattern {
-			kind: PatternKind::Cons(f, Arc::new(subs)),
-			hash: AtomicU64::new(0)
-		}
+		Pattern::from_kind(PatternKind::Cons(f, Arc::new(subs)))
 	}
 
 	pub fn from_slice(f: F, subs: &[Self]) -> Self where F: Clone, X: Clone {
-		Pattern {
-			kind: PatternKind::Cons(f, Arc::new(subs.iter().map(|p| p.clone()).collect())),
-			hash: AtomicU64::new(0)
-		}
+		Pattern::from_kind(PatternKind::Cons(f, Arc::new(subs.to_vec())))
 	}
 
 	pub fn var(x: X) -> Self {
-		Pattern {
-			kind: PatternKind::Var(x),
-			hash: AtomicU64::new(0)
-		}
+		Pattern::from_kind(PatternKind::Var(x))
+	}
+
+	/// Number of symbol and variable occurrences in the pattern.
+	pub fn size(&self) -> usize {
+		self.size
+	}
+
+	/// Depth of the pattern (0 for constants and variables).
+	pub fn depth(&self) -> u64 {
+		self.depth
+	}
+
+	/// Number of variable occurrences in the pattern.
+	pub fn variable_count(&self) -> usize {
+		self.variables
+	}
+
+	/// Check if the pattern contains no variable.
+	pub fn is_ground(&self) -> bool {
+		self.variables == 0
 	}
 
 	pub fn symbol(&self) -> Option<&F> {
@@ -154,6 +196,10 @@ impl<F, X> Pattern<F, X> {
 	}
 
 	pub fn as_term(&self) -> Option<Term<F>> where F: Clone {
+		if !self.is_ground() {
+			return None
+		}
+
 		match &self.kind {
 			PatternKind::Var(_) => None,
 			PatternKind::Cons(f, sub_patterns) => {
@@ -170,7 +216,7 @@ impl<F, X> Pattern<F, X> {
 		}
 	}
 
-	pub fn variables(&self) -> UniqueVariables<X> where X: PartialEq {
+	pub fn variables(&self) -> UniqueVariables<'_, X> where X: PartialEq {
 		UniqueVariables::new(self)
 	}
 
@@ -185,10 +231,7 @@ impl<F, X> Pattern<F, X> {
 			}
 		};
 
-		Pattern {
-			kind: kind,
-			hash: AtomicU64::new(0)
-		}
+		Pattern::from_kind(kind)
 	}
 
 	pub fn try_map_variables<Y, M>(&self, g: &M) -> Option<Pattern<F, Y>> where M: Fn(&X) -> Option<Pattern<F, Y>>, F: Clone {
@@ -211,14 +254,11 @@ impl<F, X> Pattern<F, X> {
 			}
 		};
 
-		Some(Pattern {
-			kind: kind,
-			hash: AtomicU64::new(0)
-		})
+		Some(Pattern::from_kind(kind))
 	}
 
 	/// Find a renaming from X -> Y so that both patterns are equals.
-	pub fn renaming<Z: Clone, W: Clone, Y: AsRef<W>>(&self, other: &Pattern<F, Y>, renaming: &mut HashMap<Z, W>) -> bool where X: AsRef<Z> + PartialOrd<Y>, Z: Hash + Eq, W: Eq, F: PartialEq {
+	pub fn renaming<Z, W, Y>(&self, other: &Pattern<F, Y>, renaming: &mut HashMap<Z, W>) -> bool where X: AsRef<Z> + PartialOrd<Y>, Y: AsRef<W>, Z: Clone + Hash + Eq, W: Clone + Eq, F: PartialEq {
 		match (self.kind(), other.kind()) {
 			(PatternKind::Cons(f1, subs1), PatternKind::Cons(f2, subs2)) if f1 == f2 && subs1.len() == subs2.len() => {
 				for i in 0..subs1.len() {
@@ -235,11 +275,7 @@ impl<F, X> Pattern<F, X> {
 			(PatternKind::Var(x), PatternKind::Var(y)) => {
 				let z = x.as_ref();
 				if let Some(w) = renaming.get(z) {
-					if w == y.as_ref() {
-						true
-					} else {
-						false
-					}
+					w == y.as_ref()
 				} else {
 					if x <= y {
 						renaming.insert(z.clone(), y.as_ref().clone());
@@ -252,14 +288,72 @@ impl<F, X> Pattern<F, X> {
 			_ => false
 		}
 	}
+	/// Build a pattern from any pattern-like value, such as a term.
+	pub fn from_like<T: PatternLike<F, X>>(t: &T) -> Self where F: Clone, X: Clone {
+		match t.kind() {
+			PatternLikeKind::Cons(f, subs) => {
+				Pattern::cons(f.clone(), subs.iter().map(Pattern::from_like).collect())
+			},
+			PatternLikeKind::Var(x) => Pattern::var(x.clone())
+		}
+	}
+
+	/// Check if the pattern is equal to the given pattern-like value.
+	pub fn eq_like<T: PatternLike<F, X>>(&self, other: &T) -> bool where F: PartialEq, X: PartialEq {
+		match (&self.kind, other.kind()) {
+			(PatternKind::Cons(f, subs), PatternLikeKind::Cons(g, other_subs)) => {
+				f == g && subs.len() == other_subs.len() && subs.iter().zip(other_subs).all(|(a, b)| a.eq_like(b))
+			},
+			(PatternKind::Var(x), PatternLikeKind::Var(y)) => x == y,
+			_ => false
+		}
+	}
+
+	/// Syntactic matching of the pattern against a term.
+	///
+	/// Returns the substitution `sigma` such that `sigma(self) = term`, if any.
+	/// Variables occurring more than once in the pattern must be bound to equal sub-terms.
+	pub fn matches(&self, term: &Term<F>) -> Option<Substitution<F, X>> where F: Clone + PartialEq, X: Clone + PartialEq {
+		self.matches_like(term)
+	}
+
+	/// Syntactic matching of the pattern against any pattern-like value.
+	///
+	/// Variables of the subject are treated as constants.
+	pub fn matches_like<T: PatternLike<F, X>>(&self, subject: &T) -> Option<Substitution<F, X>> where F: Clone + PartialEq, X: Clone + PartialEq {
+		let mut sigma = Substitution::new();
+		if self.match_with(subject, &mut sigma) {
+			Some(sigma)
+		} else {
+			None
+		}
+	}
+
+	/// Extend the given substitution so that the pattern matches the subject.
+	///
+	/// When the matching fails, the substitution may have been partially extended.
+	pub fn match_with<T: PatternLike<F, X>>(&self, subject: &T, sigma: &mut Substitution<F, X>) -> bool where F: Clone + PartialEq, X: Clone + PartialEq {
+		match (&self.kind, subject.kind()) {
+			(PatternKind::Var(x), _) => {
+				match sigma.get(x) {
+					Some(p) => p.eq_like(subject),
+					None => {
+						sigma.bind(x.clone(), Pattern::from_like(subject));
+						true
+					}
+				}
+			},
+			(PatternKind::Cons(f, subs), PatternLikeKind::Cons(g, subject_subs)) if f == g && subs.len() == subject_subs.len() => {
+				subs.iter().zip(subject_subs).all(|(a, b)| a.match_with(b, sigma))
+			},
+			_ => false
+		}
+	}
 }
 
 impl<F, X> From<PatternKind<F, X>> for Pattern<F, X> {
 	fn from(kind: PatternKind<F, X>) -> Pattern<F, X> {
-		Pattern {
-			kind: kind,
-			hash: AtomicU64::new(0)
-		}
+		Pattern::from_kind(kind)
 	}
 }
 
@@ -298,15 +392,10 @@ impl<'a, X: PartialEq> Iterator for UniqueVariables<'a, X> {
 			},
 			UniqueVariables::Cons(ref mut iterators, ref mut visited) => {
 				for it in iterators.iter_mut() {
-					loop {
-						match it.next() {
-							Some(x) => {
-								if !visited.contains(&x) {
-									visited.push(x);
-									return Some(x)
-								}
-							},
-							None => break
+					for x in it {
+						if !visited.contains(&x) {
+							visited.push(x);
+							return Some(x)
 						}
 					}
 				}
@@ -342,7 +431,10 @@ impl<F: Clone, X: Clone> Clone for Pattern<F, X> {
 			PatternKind::Var(x) => PatternKind::Var(x.clone())
 		};
 		Pattern {
-			kind: kind,
+			kind,
+			size: self.size,
+			depth: self.depth,
+			variables: self.variables,
 			hash: AtomicU64::new(self.hash.load(atomic::Ordering::Relaxed))
 		}
 	}
@@ -350,6 +442,10 @@ impl<F: Clone, X: Clone> Clone for Pattern<F, X> {
 
 impl<F: PartialEq, X: PartialEq> PartialEq for Pattern<F, X> {
 	fn eq(&self, other: &Pattern<F, X>) -> bool {
+		if self.size != other.size {
+			return false
+		}
+
 		match (&self.kind, &other.kind) {
 			(PatternKind::Cons(f1, subs1), PatternKind::Cons(f2, subs2)) => {
 				f1 == f2 && subs1 == subs2
@@ -401,9 +497,6 @@ impl<F: Hash, X: Hash> Hash for Pattern<F, X> {
 
 impl<F, X> From<X> for Pattern<F, X> {
 	fn from(x: X) -> Self {
-		Pattern {
-			kind: PatternKind::Var(x),
-			hash: AtomicU64::new(0)
-		}
+		Pattern::from_kind(PatternKind::Var(x))
 	}
 }
@@ -0,0 +1,209 @@
+use std::sync::Arc;
+use std::fmt;
+use std::str::FromStr;
+use std::num::ParseIntError;
+use crate::{Term, Pattern, PatternKind};
+
+/// A position in a term or pattern.
+///
+/// It is the sequence of child indexes (starting from 0) to follow from the root to reach a sub-term.
+/// Positions are ordered lexicographically, so a position comes before any of its extensions.
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Position(Vec<usize>);
+
+impl Position {
+	/// The root position.
+	pub fn root() -> Position {
+		Position(Vec::new())
+	}
+
+	pub fn new(indexes: Vec<usize>) -> Position {
+		Position(indexes)
+	}
+
+	pub fn is_root(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Length of the position, that is the depth of the sub-term it addresses.
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	pub fn indexes(&self) -> &[usize] {
+		&self.0
+	}
+
+	pub fn push(&mut self, i: usize) {
+		self.0.push(i)
+	}
+
+	pub fn pop(&mut self) -> Option<usize> {
+		self.0.pop()
+	}
+
+	/// The position of the `i`-th child of the sub-term at this position.
+	pub fn child(&self, i: usize) -> Position {
+		let mut indexes = Vec::with_capacity(self.0.len() + 1);
+		indexes.extend_from_slice(&self.0);
+		indexes.push(i);
+		Position(indexes)
+	}
+
+	/// The position of the parent sub-term, or `None` for the root position.
+	pub fn parent(&self) -> Option<Position> {
+		self.0.split_last().map(|(_, init)| Position(init.to_vec()))
+	}
+
+	/// Concatenation of the two positions.
+	pub fn concat(&self, other: &Position) -> Position {
+		let mut indexes = Vec::with_capacity(self.0.len() + other.0.len());
+		indexes.extend_from_slice(&self.0);
+		indexes.extend_from_slice(&other.0);
+		Position(indexes)
+	}
+
+	/// Check if this position is a prefix of (or equal to) the other.
+	pub fn is_prefix_of(&self, other: &Position) -> bool {
+		other.0.starts_with(&self.0)
+	}
+
+	/// Check if this position is a strict prefix of the other.
+	pub fn is_strict_prefix_of(&self, other: &Position) -> bool {
+		self.0.len() < other.0.len() && self.is_prefix_of(other)
+	}
+
+	/// Check if none of the two positions is a prefix of the other.
+	pub fn is_parallel_to(&self, other: &Position) -> bool {
+		!self.is_prefix_of(other) && !other.is_prefix_of(self)
+	}
+
+	/// Remove the given prefix from the position.
+	///
+	/// Returns `None` if it is not a prefix of this position.
+	pub fn strip_prefix(&self, prefix: &Position) -> Option<Position> {
+		if prefix.is_prefix_of(self) {
+			Some(Position(self.0[prefix.0.len()..].to_vec()))
+		} else {
+			None
+		}
+	}
+}
+
+impl From<Vec<usize>> for Position {
+	fn from(indexes: Vec<usize>) -> Position {
+		Position(indexes)
+	}
+}
+
+impl fmt::Display for Position {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.0.split_first() {
+			Some((head, tail)) => {
+				head.fmt(f)?;
+				for i in tail.iter() {
+					write!(f, ".{}", i)?;
+				}
+				Ok(())
+			},
+			None => write!(f, "ε")
+		}
+	}
+}
+
+impl FromStr for Position {
+	type Err = ParseIntError;
+
+	/// Parse a position of the form `1.2.3`. The root position is `ε` or the empty string.
+	fn from_str(s: &str) -> Result<Position, ParseIntError> {
+		let s = s.trim();
+		if s.is_empty() || s == "ε" {
+			Ok(Position::root())
+		} else {
+			let mut indexes = Vec::new();
+			for i in s.split('.') {
+				indexes.push(i.trim().parse()?)
+			}
+			Ok(Position(indexes))
+		}
+	}
+}
+
+impl<F> Term<F> {
+	/// Get the sub-term at the given position.
+	pub fn at(&self, position: &Position) -> Option<&Term<F>> {
+		let mut term = self;
+		for i in position.indexes() {
+			term = term.sub_terms().get(*i)?
+		}
+
+		Some(term)
+	}
+
+	/// Replace the sub-term at the given position.
+	///
+	/// Every sub-term not on the path to the position is shared with the original term.
+	/// Returns `None` if the position does not exist in the term.
+	pub fn replace_at(&self, position: &Position, replacement: Term<F>) -> Option<Term<F>> where F: Clone {
+		self.replace_at_indexes(position.indexes(), replacement)
+	}
+
+	fn replace_at_indexes(&self, indexes: &[usize], replacement: Term<F>) -> Option<Term<F>> where F: Clone {
+		match indexes.split_first() {
+			Some((i, tail)) => {
+				let sub = self.sub_terms().get(*i)?.replace_at_indexes(tail, replacement)?;
+				let mut subs = self.sub_terms().clone();
+				subs[*i] = sub;
+				Some(Term::new(self.symbol().clone(), subs))
+			},
+			None => Some(replacement)
+		}
+	}
+
+	/// List every position of the term, in pre-order.
+	pub fn positions(&self) -> Vec<Position> {
+		self.pre_order().with_positions().map(|(position, _)| position).collect()
+	}
+}
+
+impl<F, X> Pattern<F, X> {
+	/// Get the sub-pattern at the given position.
+	pub fn at(&self, position: &Position) -> Option<&Pattern<F, X>> {
+		let mut pattern = self;
+		for i in position.indexes() {
+			pattern = pattern.get(*i)?
+		}
+
+		Some(pattern)
+	}
+
+	/// Replace the sub-pattern at the given position.
+	///
+	/// Every sub-pattern not on the path to the position is shared with the original pattern.
+	/// Returns `None` if the position does not exist in the pattern.
+	pub fn replace_at(&self, position: &Position, replacement: Pattern<F, X>) -> Option<Pattern<F, X>> where F: Clone, X: Clone {
+		self.replace_at_indexes(position.indexes(), replacement)
+	}
+
+	fn replace_at_indexes(&self, indexes: &[usize], replacement: Pattern<F, X>) -> Option<Pattern<F, X>> where F: Clone, X: Clone {
+		match indexes.split_first() {
+			Some((i, tail)) => {
+				let (f, subs) = self.as_cons()?;
+				let sub = subs.get(*i)?.replace_at_indexes(tail, replacement)?;
+				let mut subs = subs.clone();
+				subs[*i] = sub;
+				Some(PatternKind::Cons(f.clone(), Arc::new(subs)).into())
+			},
+			None => Some(replacement)
+		}
+	}
+
+	/// List every position of the pattern, in pre-order.
+	pub fn positions(&self) -> Vec<Position> {
+		self.pre_order().with_positions().map(|(position, _)| position).collect()
+	}
+}
@@ -0,0 +1,292 @@
+use std::fmt;
+use rand::Rng;
+use crate::{Term, Pattern, Ranked, Index};
+use crate::variable::Spawnable;
+use crate::counting::{Counter, mul};
+
+/// Random generation error.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RandomError {
+	/// A leaf is needed but the alphabet contains no constant of positive weight (and variables are disabled).
+	NoConstant,
+
+	/// The maximum size is 0.
+	ZeroSize,
+
+	/// There is no term of the requested size.
+	NoTerm,
+
+	/// The number of terms of the requested size does not fit in a `u128`.
+	Overflow
+}
+
+impl fmt::Display for RandomError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			RandomError::NoConstant => write!(f, "no constant symbol available"),
+			RandomError::ZeroSize => write!(f, "the maximum size is 0"),
+			RandomError::NoTerm => write!(f, "no term of the requested size"),
+			RandomError::Overflow => write!(f, "too many terms of the requested size")
+		}
+	}
+}
+
+impl std::error::Error for RandomError {}
+
+/// Default maximum depth of the terms generated by a [`Generator`].
+pub const DEFAULT_MAX_DEPTH: u64 = 16;
+
+/// Default maximum size of the terms generated by a [`Generator`].
+pub const DEFAULT_MAX_SIZE: usize = 256;
+
+/// Random term and pattern generator.
+///
+/// At each position, a symbol is chosen with a probability proportional to its weight,
+/// among the symbols that do not exceed the maximum depth and size.
+/// The generation is deterministic for a given random number generator state.
+pub struct Generator<F> {
+	symbols: Vec<(F, f64)>,
+	max_depth: u64,
+	max_size: usize,
+	variable_probability: f64
+}
+
+enum Node<F> {
+	Cons(F, usize),
+	Var
+}
+
+impl<F: Clone + Ranked> Generator<F> {
+	/// Create a generator where every symbol of the alphabet has the same weight.
+	///
+	/// The depth and size are bounded by [`DEFAULT_MAX_DEPTH`] and [`DEFAULT_MAX_SIZE`].
+	pub fn new(alphabet: &[F]) -> Generator<F> {
+		Self::with_weights(alphabet.iter().map(|f| (f.clone(), 1.0)).collect())
+	}
+
+	/// Create a generator with the given symbol weights.
+	///
+	/// Symbols with a weight of 0 are never generated.
+	/// The depth and size are bounded by [`DEFAULT_MAX_DEPTH`] and [`DEFAULT_MAX_SIZE`].
+	pub fn with_weights(symbols: Vec<(F, f64)>) -> Generator<F> {
+		Generator {
+			symbols,
+			max_depth: DEFAULT_MAX_DEPTH,
+			max_size: DEFAULT_MAX_SIZE,
+			variable_probability: 0.0
+		}
+	}
+
+	/// Set the maximum depth of the generated terms.
+	pub fn max_depth(mut self, max_depth: u64) -> Generator<F> {
+		self.max_depth = max_depth;
+		self
+	}
+
+	/// Set the maximum size of the generated terms.
+	///
+	/// When the expected number of sub-terms of a symbol is at least 1,
+	/// the generation may only terminate because of this bound.
+	pub fn max_size(mut self, max_size: usize) -> Generator<F> {
+		self.max_size = max_size;
+		self
+	}
+
+	/// Set the probability for each position of a generated pattern to be a variable.
+	pub fn variable_probability(mut self, p: f64) -> Generator<F> {
+		self.variable_probability = p;
+		self
+	}
+
+	/// Choose a symbol of arity at most `max_arity`, or a variable if `variables` is true.
+	fn choose<R: Rng + ?Sized>(&self, rng: &mut R, max_arity: usize, variables: bool) -> Result<Node<F>, RandomError> {
+		if variables && rng.gen::<f64>() < self.variable_probability {
+			return Ok(Node::Var)
+		}
+
+		let total: f64 = self.symbols.iter().filter(|(f, w)| *w > 0.0 && f.arity() <= max_arity).map(|(_, w)| w).sum();
+		if total <= 0.0 {
+			return if variables && self.variable_probability > 0.0 {
+				Ok(Node::Var)
+			} else {
+				Err(RandomError::NoConstant)
+			}
+		}
+
+		let mut x = rng.gen::<f64>() * total;
+		let mut chosen = None;
+		for (f, w) in self.symbols.iter().filter(|(f, w)| *w > 0.0 && f.arity() <= max_arity) {
+			chosen = Some(f);
+			if x < *w {
+				break
+			}
+			x -= w;
+		}
+
+		let f = chosen.unwrap();
+		Ok(Node::Cons(f.clone(), f.arity()))
+	}
+
+	/// Generate the pre-order sequence of nodes.
+	fn nodes<R: Rng + ?Sized>(&self, rng: &mut R, variables: bool) -> Result<Vec<Node<F>>, RandomError> {
+		if self.max_size == 0 {
+			return Err(RandomError::ZeroSize)
+		}
+
+		let mut nodes = Vec::new();
+		// depth of each pending position.
+		let mut pending = vec![0u64];
+		while let Some(depth) = pending.pop() {
+			// every pending position needs at least one node.
+			let available = self.max_size - nodes.len() - pending.len() - 1;
+			let max_arity = if depth >= self.max_depth { 0 } else { available };
+			let node = self.choose(rng, max_arity, variables)?;
+			if let Node::Cons(_, arity) = &node {
+				for _ in 0..*arity {
+					pending.push(depth + 1)
+				}
+			}
+			nodes.push(node)
+		}
+
+		Ok(nodes)
+	}
+
+	/// Generate a random term.
+	pub fn term<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Term<F>, RandomError> {
+		let nodes = self.nodes(rng, false)?;
+		let mut stack: Vec<Term<F>> = Vec::new();
+		for node in nodes.into_iter().rev() {
+			if let Node::Cons(f, arity) = node {
+				let mut subs = stack.split_off(stack.len() - arity);
+				subs.reverse();
+				stack.push(Term::new(f, subs))
+			}
+		}
+
+		Ok(stack.pop().unwrap())
+	}
+
+	/// Generate a random pattern, using the given function to create variables.
+	pub fn pattern<X: Clone, R: Rng + ?Sized, V: FnMut(&mut R) -> X>(&self, rng: &mut R, mut variable: V) -> Result<Pattern<F, X>, RandomError> {
+		let nodes = self.nodes(rng, true)?;
+		let mut stack: Vec<Pattern<F, X>> = Vec::new();
+		for node in nodes.into_iter().rev() {
+			match node {
+				Node::Cons(f, arity) => {
+					let mut subs = stack.split_off(stack.len() - arity);
+					subs.reverse();
+					stack.push(Pattern::cons(f, subs))
+				},
+				Node::Var => stack.push(Pattern::var(variable(rng)))
+			}
+		}
+
+		Ok(stack.pop().unwrap())
+	}
+
+	/// Generate a random linear pattern, with fresh variables spawned in the given namespace.
+	pub fn linear_pattern<X: Spawnable, R: Rng + ?Sized>(&self, rng: &mut R, namespace: &X::Namespace) -> Result<Pattern<F, X>, RandomError> {
+		self.pattern(rng, |_| X::spawn(namespace))
+	}
+
+	/// Generate a random pattern whose variables are chosen uniformly among the `count` first indexes.
+	pub fn indexed_pattern<X: Index, R: Rng + ?Sized>(&self, rng: &mut R, count: usize) -> Result<Pattern<F, X>, RandomError> {
+		self.pattern(rng, |rng| {
+			let mut x = X::ZERO;
+			for _ in 0..rng.gen_range(0, count.max(1)) {
+				x = x.next()
+			}
+			x
+		})
+	}
+}
+
+/// Uniform random term generator.
+///
+/// Every term of the requested size has the same probability to be generated.
+/// It uses the recursive method: the terms of each size are counted (see [`Counter`])
+/// and the symbol and sub-term sizes are chosen at each position with a probability
+/// proportional to the number of terms they lead to.
+///
+/// The sampling is exact as long as the number of terms of the requested size fits in a `u128`.
+pub struct UniformGenerator<F> {
+	counter: Counter<F>
+}
+
+/// Choose an index with a probability proportional to its weight.
+fn pick<R: Rng + ?Sized, I: Iterator<Item = Option<u128>>>(rng: &mut R, total: u128, weights: I) -> Result<usize, RandomError> {
+	let mut x = rng.gen_range(0, total);
+	for (i, w) in weights.enumerate() {
+		let w = w.ok_or(RandomError::Overflow)?;
+		if x < w {
+			return Ok(i)
+		}
+		x -= w
+	}
+
+	unreachable!()
+}
+
+impl<F: Clone + Ranked> UniformGenerator<F> {
+	pub fn new(alphabet: &[F]) -> UniformGenerator<F> {
+		UniformGenerator {
+			counter: Counter::new(alphabet.to_vec())
+		}
+	}
+
+	/// Number of terms of the given size, or `None` if it does not fit in a `u128`.
+	pub fn count(&mut self, size: usize) -> Option<u128> {
+		self.counter.count(size)
+	}
+
+	/// Generate a random term of the given size, uniformly.
+	pub fn term<R: Rng + ?Sized>(&mut self, rng: &mut R, size: usize) -> Result<Term<F>, RandomError> {
+		if size == 0 {
+			return Err(RandomError::ZeroSize)
+		}
+
+		match self.counter.count(size) {
+			None => return Err(RandomError::Overflow),
+			Some(0) => return Err(RandomError::NoTerm),
+			Some(_) => ()
+		}
+
+		let mut nodes = Vec::new();
+		// size of each pending position.
+		let mut pending = vec![size];
+		let mut sizes = Vec::new();
+		while let Some(n) = pending.pop() {
+			let total = self.counter.count(n).unwrap();
+			let counter = &mut self.counter;
+			let arities: Vec<usize> = counter.alphabet().iter().map(Ranked::arity).collect();
+			let i = pick(rng, total, arities.iter().map(|k| counter.count_sequences(*k, n - 1)))?;
+			let f = self.counter.alphabet()[i].clone();
+			let arity = arities[i];
+
+			// split the remaining size among the sub-terms.
+			let mut m = n - 1;
+			sizes.clear();
+			for j in 0..arity {
+				let rest = arity - j - 1;
+				let total = self.counter.count_sequences(arity - j, m).ok_or(RandomError::Overflow)?;
+				let counter = &mut self.counter;
+				let s = 1 + pick(rng, total, (1..=(m - rest)).map(|s| mul(counter.count(s), counter.count_sequences(rest, m - s))))?;
+				sizes.push(s);
+				m -= s
+			}
+
+			pending.extend(sizes.iter().rev());
+			nodes.push((f, arity))
+		}
+
+		let mut stack: Vec<Term<F>> = Vec::new();
+		for (f, arity) in nodes.into_iter().rev() {
+			let mut subs = stack.split_off(stack.len() - arity);
+			subs.reverse();
+			stack.push(Term::new(f, subs))
+		}
+
+		Ok(stack.pop().unwrap())
+	}
+}
@@ -0,0 +1,384 @@
+use std::fmt;
+use crate::{Term, Pattern, PatternKind, Position};
+
+/// Invalid rewrite rule.
+pub enum RuleError<X> {
+	/// The left-hand side is a variable.
+	VariableLhs,
+
+	/// The given variable of the right-hand side does not occur in the left-hand side.
+	UnboundVariable(X)
+}
+
+impl<X: fmt::Debug> fmt::Debug for RuleError<X> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			RuleError::VariableLhs => write!(f, "VariableLhs"),
+			RuleError::UnboundVariable(x) => write!(f, "UnboundVariable({:?})", x)
+		}
+	}
+}
+
+impl<X: fmt::Display> fmt::Display for RuleError<X> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			RuleError::VariableLhs => write!(f, "the left-hand side is a variable"),
+			RuleError::UnboundVariable(x) => write!(f, "variable {} does not occur in the left-hand side", x)
+		}
+	}
+}
+
+impl<X: fmt::Debug + fmt::Display> std::error::Error for RuleError<X> {}
+
+/// An equation between two patterns.
+pub type Equation<F, X> = (Pattern<F, X>, Pattern<F, X>);
+
+/// A rewrite rule `lhs -> rhs`.
+///
+/// The left-hand side is not a variable, and every variable of the right-hand side occurs in the left-hand side.
+pub struct Rule<F, X> {
+	lhs: Pattern<F, X>,
+	rhs: Pattern<F, X>
+}
+
+impl<F, X> Rule<F, X> {
+	/// Create a new rule, checking its validity.
+	pub fn new(lhs: Pattern<F, X>, rhs: Pattern<F, X>) -> Result<Rule<F, X>, RuleError<X>> where X: Clone + PartialEq {
+		if let PatternKind::Var(_) = lhs.kind() {
+			return Err(RuleError::VariableLhs)
+		}
+
+		for x in rhs.variables() {
+			if !lhs.variables().any(|y| x == y) {
+				return Err(RuleError::UnboundVariable(x.clone()))
+			}
+		}
+
+		Ok(Rule {
+			lhs,
+			rhs
+		})
+	}
+
+	/// Create a new rule without checking its validity.
+	pub(crate) fn new_unchecked(lhs: Pattern<F, X>, rhs: Pattern<F, X>) -> Rule<F, X> {
+		Rule {
+			lhs,
+			rhs
+		}
+	}
+
+	pub fn lhs(&self) -> &Pattern<F, X> {
+		&self.lhs
+	}
+
+	pub fn rhs(&self) -> &Pattern<F, X> {
+		&self.rhs
+	}
+
+	/// Rewrite the given term at its root position, if the left-hand side matches it.
+	pub fn apply(&self, term: &Term<F>) -> Option<Term<F>> where F: Clone + PartialEq, X: Clone + PartialEq {
+		let sigma = self.lhs.matches(term)?;
+		sigma.instantiate(&self.rhs)
+	}
+
+	/// Rewrite the given pattern at its root position, if the left-hand side matches it.
+	///
+	/// Variables of the pattern are treated as constants.
+	pub fn apply_pattern(&self, pattern: &Pattern<F, X>) -> Option<Pattern<F, X>> where F: Clone + PartialEq, X: Clone + PartialEq {
+		let sigma = self.lhs.matches_like(pattern)?;
+		Some(sigma.apply(&self.rhs))
+	}
+}
+
+impl<F: Clone, X: Clone> Clone for Rule<F, X> {
+	fn clone(&self) -> Rule<F, X> {
+		Rule {
+			lhs: self.lhs.clone(),
+			rhs: self.rhs.clone()
+		}
+	}
+}
+
+impl<F: PartialEq, X: PartialEq> PartialEq for Rule<F, X> {
+	fn eq(&self, other: &Rule<F, X>) -> bool {
+		self.lhs == other.lhs && self.rhs == other.rhs
+	}
+}
+
+impl<F: Eq, X: Eq> Eq for Rule<F, X> {}
+
+impl<F: fmt::Debug, X: fmt::Debug> fmt::Debug for Rule<F, X> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:?} -> {:?}", self.lhs, self.rhs)
+	}
+}
+
+impl<F: fmt::Display, X: fmt::Display> fmt::Display for Rule<F, X> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} -> {}", self.lhs, self.rhs)
+	}
+}
+
+/// Rewriting strategy, deciding which redex is contracted at each step.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Strategy {
+	/// Contract the leftmost innermost redex (call-by-value).
+	Innermost,
+
+	/// Contract an outermost redex, choosing the one matched by the first rule (and then the leftmost).
+	Outermost,
+
+	/// Contract every outermost redex at once.
+	ParallelOutermost,
+
+	/// Contract the leftmost redex, which is the leftmost outermost redex (call-by-name).
+	Leftmost
+}
+
+/// Result of a normalization.
+pub struct Normalization<F> {
+	/// Last term of the reduction.
+	pub term: Term<F>,
+
+	/// Number of steps performed.
+	pub steps: usize,
+
+	/// Tells if the term is in normal form, or if the reduction stopped because of the steps limit.
+	pub normal: bool
+}
+
+/// A term rewriting system: an ordered list of rules.
+///
+/// When multiple rules apply at the same position, the first one is used.
+pub struct RewriteSystem<F, X> {
+	rules: Vec<Rule<F, X>>
+}
+
+impl<F, X> RewriteSystem<F, X> {
+	pub fn new() -> RewriteSystem<F, X> {
+		RewriteSystem {
+			rules: Vec::new()
+		}
+	}
+
+	pub fn rules(&self) -> &[Rule<F, X>] {
+		&self.rules
+	}
+
+	pub fn len(&self) -> usize {
+		self.rules.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.rules.is_empty()
+	}
+
+	pub fn add(&mut self, rule: Rule<F, X>) {
+		self.rules.push(rule)
+	}
+}
+
+impl<F: Clone + PartialEq, X: Clone + PartialEq> RewriteSystem<F, X> {
+	/// Index of the first rule rewriting the given term at its root position, with the reduct.
+	fn contract(&self, term: &Term<F>) -> Option<(usize, Term<F>)> {
+		self.rules.iter().enumerate().find_map(|(i, rule)| rule.apply(term).map(|reduct| (i, reduct)))
+	}
+
+	/// Rewrite the given term at its root position, using the first applicable rule.
+	pub fn rewrite(&self, term: &Term<F>) -> Option<Term<F>> {
+		self.contract(term).map(|(_, reduct)| reduct)
+	}
+
+	/// Rewrite the sub-term at the given position, using the first applicable rule.
+	pub fn rewrite_at(&self, term: &Term<F>, position: &Position) -> Option<Term<F>> {
+		let reduct = self.rewrite(term.at(position)?)?;
+		term.replace_at(position, reduct)
+	}
+
+	/// Check if the term is a redex (its root position can be rewritten).
+	pub fn is_redex(&self, term: &Term<F>) -> bool {
+		self.rewrite(term).is_some()
+	}
+
+	/// Every position of the term where a rule applies, in pre-order.
+	pub fn redexes(&self, term: &Term<F>) -> Vec<Position> {
+		term.pre_order().with_positions().filter(|(_, sub)| self.is_redex(sub)).map(|(position, _)| position).collect()
+	}
+
+	/// Every outermost redex position, in pre-order, with the index of the first rule that applies and the reduct.
+	fn outermost_redexes(&self, term: &Term<F>) -> Vec<(Position, usize, Term<F>)> {
+		let mut redexes = Vec::new();
+		let mut stack = vec![(term, Position::root())];
+		while let Some((sub, position)) = stack.pop() {
+			match self.contract(sub) {
+				Some((i, reduct)) => redexes.push((position, i, reduct)),
+				None => {
+					for (i, sub_sub) in sub.sub_terms().iter().enumerate().rev() {
+						stack.push((sub_sub, position.child(i)))
+					}
+				}
+			}
+		}
+
+		redexes
+	}
+
+	pub fn is_normal_form(&self, term: &Term<F>) -> bool {
+		!term.pre_order().any(|sub| self.is_redex(sub))
+	}
+
+	/// The position of the redex contracted by the given single-step strategy, with its reduct.
+	///
+	/// Returns `None` for the parallel strategy.
+	fn select(&self, term: &Term<F>, strategy: Strategy) -> Option<(Position, Term<F>)> {
+		match strategy {
+			Strategy::Innermost => {
+				term.post_order().with_positions().find_map(|(position, sub)| Some((position, self.rewrite(sub)?)))
+			},
+			Strategy::Outermost => {
+				let redexes = self.outermost_redexes(term);
+				let first_rule = redexes.iter().map(|(_, i, _)| *i).min()?;
+				redexes.into_iter().find(|(_, i, _)| *i == first_rule).map(|(position, _, reduct)| (position, reduct))
+			},
+			Strategy::Leftmost => {
+				term.pre_order().with_positions().find_map(|(position, sub)| Some((position, self.rewrite(sub)?)))
+			},
+			Strategy::ParallelOutermost => None
+		}
+	}
+
+	/// Perform one step of the given strategy.
+	///
+	/// Returns `None` if the term is in normal form.
+	pub fn step(&self, term: &Term<F>, strategy: Strategy) -> Option<Term<F>> {
+		match strategy {
+			Strategy::ParallelOutermost => self.parallel_outermost_step(term),
+			_ => {
+				let (position, reduct) = self.select(term, strategy)?;
+				term.replace_at(&position, reduct)
+			}
+		}
+	}
+
+	fn parallel_outermost_step(&self, term: &Term<F>) -> Option<Term<F>> {
+		let redexes = self.outermost_redexes(term);
+		if redexes.is_empty() {
+			return None
+		}
+
+		let mut result = term.clone();
+		for (position, _, reduct) in redexes {
+			result = result.replace_at(&position, reduct)?;
+		}
+
+		Some(result)
+	}
+
+	/// Normalize the term with the given strategy, performing at most `limit` steps.
+	pub fn normalize(&self, term: &Term<F>, strategy: Strategy, limit: usize) -> Normalization<F> {
+		let mut term = term.clone();
+		let mut steps = 0;
+		loop {
+			if steps >= limit {
+				let normal = self.is_normal_form(&term);
+				return Normalization {
+					term,
+					steps,
+					normal
+				}
+			}
+
+			match self.step(&term, strategy) {
+				Some(next) => {
+					term = next;
+					steps += 1;
+				},
+				None => {
+					return Normalization {
+						term,
+						steps,
+						normal: true
+					}
+				}
+			}
+		}
+	}
+}
+
+impl<F: Clone + PartialEq, X: Clone + PartialEq> RewriteSystem<F, X> {
+	/// Rewrite the given pattern at its root position, using the first applicable rule.
+	///
+	/// Variables of the pattern are treated as constants.
+	pub fn rewrite_pattern(&self, pattern: &Pattern<F, X>) -> Option<Pattern<F, X>> {
+		self.rules.iter().find_map(|rule| rule.apply_pattern(pattern))
+	}
+
+	/// Check if the pattern contains no redex.
+	pub fn is_normal_pattern(&self, pattern: &Pattern<F, X>) -> bool {
+		!pattern.pre_order().any(|sub| self.rules.iter().any(|rule| rule.lhs.matches_like(sub).is_some()))
+	}
+
+	/// Contract the leftmost innermost redex of the pattern.
+	///
+	/// Returns `None` if the pattern is in normal form.
+	pub fn pattern_step(&self, pattern: &Pattern<F, X>) -> Option<Pattern<F, X>> {
+		pattern.post_order().with_positions().find_map(|(position, sub)| {
+			let reduct = self.rewrite_pattern(sub)?;
+			pattern.replace_at(&position, reduct)
+		})
+	}
+
+	/// Normalize the pattern with the innermost strategy, performing at most `limit` steps.
+	///
+	/// Returns the last pattern of the reduction, and tells if it is in normal form.
+	pub fn normalize_pattern(&self, pattern: &Pattern<F, X>, limit: usize) -> (Pattern<F, X>, bool) {
+		let mut pattern = pattern.clone();
+		for _ in 0..limit {
+			match self.pattern_step(&pattern) {
+				Some(next) => pattern = next,
+				None => return (pattern, true)
+			}
+		}
+
+		let normal = self.is_normal_pattern(&pattern);
+		(pattern, normal)
+	}
+}
+
+impl<F, X> Default for RewriteSystem<F, X> {
+	fn default() -> RewriteSystem<F, X> {
+		RewriteSystem::new()
+	}
+}
+
+impl<F: Clone, X: Clone> Clone for RewriteSystem<F, X> {
+	fn clone(&self) -> RewriteSystem<F, X> {
+		RewriteSystem {
+			rules: self.rules.clone()
+		}
+	}
+}
+
+impl<F: fmt::Debug, X: fmt::Debug> fmt::Debug for RewriteSystem<F, X> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_list().entries(self.rules.iter()).finish()
+	}
+}
+
+impl<F: fmt::Display, X: fmt::Display> fmt::Display for RewriteSystem<F, X> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for rule in self.rules.iter() {
+			writeln!(f, "{}", rule)?;
+		}
+		Ok(())
+	}
+}
+
+impl<F, X> std::iter::FromIterator<Rule<F, X>> for RewriteSystem<F, X> {
+	fn from_iter<I: IntoIterator<Item = Rule<F, X>>>(iter: I) -> RewriteSystem<F, X> {
+		RewriteSystem {
+			rules: iter.into_iter().collect()
+		}
+	}
+}
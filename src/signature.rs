@@ -0,0 +1,194 @@
+use std::fmt;
+use std::hash::Hash;
+use std::collections::HashMap;
+use crate::{Term, Pattern, PatternKind, Position, Ranked};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SignatureErrorKind<F> {
+	/// The symbol is not declared in the signature.
+	UnknownSymbol(F),
+
+	/// The symbol is applied to the wrong number of arguments.
+	///
+	/// Gives the symbol, its declared arity and the actual number of arguments.
+	Arity(F, usize, usize)
+}
+
+/// Well-formedness error, located by its position in the term or pattern.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SignatureError<F> {
+	pub position: Position,
+	pub kind: SignatureErrorKind<F>
+}
+
+impl<F: fmt::Display> fmt::Display for SignatureErrorKind<F> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			SignatureErrorKind::UnknownSymbol(g) => write!(f, "unknown symbol {}", g),
+			SignatureErrorKind::Arity(g, expected, found) => write!(f, "symbol {} expects {} argument(s), but {} are given", g, expected, found)
+		}
+	}
+}
+
+impl<F: fmt::Display> fmt::Display for SignatureError<F> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} at position {}", self.kind, self.position)
+	}
+}
+
+impl<F: fmt::Debug + fmt::Display> std::error::Error for SignatureError<F> {}
+
+/// A signature: a set of symbols with their arity.
+pub struct Signature<F> {
+	symbols: Vec<(F, usize)>,
+	map: HashMap<F, usize>
+}
+
+impl<F: Clone + Hash + Eq> Signature<F> {
+	pub fn new() -> Signature<F> {
+		Signature {
+			symbols: Vec::new(),
+			map: HashMap::new()
+		}
+	}
+
+	/// Create the signature of the given ranked symbols.
+	pub fn from_ranked<'a, I: IntoIterator<Item = &'a F>>(symbols: I) -> Signature<F> where F: Ranked + 'a {
+		symbols.into_iter().map(|f| (f.clone(), f.arity())).collect()
+	}
+
+	/// Declare a symbol with the given arity.
+	///
+	/// If the symbol was already declared, its arity is replaced and the previous one is returned.
+	pub fn add(&mut self, f: F, arity: usize) -> Option<usize> {
+		match self.map.get(&f) {
+			Some(i) => Some(std::mem::replace(&mut self.symbols[*i].1, arity)),
+			None => {
+				self.map.insert(f.clone(), self.symbols.len());
+				self.symbols.push((f, arity));
+				None
+			}
+		}
+	}
+
+	/// Arity of the given symbol, if it is declared.
+	pub fn arity(&self, f: &F) -> Option<usize> {
+		self.map.get(f).map(|i| self.symbols[*i].1)
+	}
+
+	pub fn contains(&self, f: &F) -> bool {
+		self.map.contains_key(f)
+	}
+
+	pub fn len(&self) -> usize {
+		self.symbols.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.symbols.is_empty()
+	}
+
+	/// Iterate over the declared symbols with their arity, in declaration order.
+	pub fn iter(&self) -> impl Iterator<Item = (&F, usize)> {
+		self.symbols.iter().map(|(f, arity)| (f, *arity))
+	}
+
+	/// Iterate over the symbols of the given arity.
+	pub fn symbols_of_arity(&self, arity: usize) -> impl Iterator<Item = &F> {
+		self.symbols.iter().filter(move |(_, n)| *n == arity).map(|(f, _)| f)
+	}
+
+	/// Iterate over the constants (symbols of arity 0).
+	pub fn constants(&self) -> impl Iterator<Item = &F> {
+		self.symbols_of_arity(0)
+	}
+
+	pub fn max_arity(&self) -> Option<usize> {
+		self.symbols.iter().map(|(_, arity)| *arity).max()
+	}
+
+	fn check_symbol(&self, f: &F, arguments: usize, position: &Position) -> Result<(), SignatureError<F>> {
+		let kind = match self.arity(f) {
+			Some(arity) if arity == arguments => return Ok(()),
+			Some(arity) => SignatureErrorKind::Arity(f.clone(), arity, arguments),
+			None => SignatureErrorKind::UnknownSymbol(f.clone())
+		};
+
+		Err(SignatureError {
+			position: position.clone(),
+			kind
+		})
+	}
+
+	/// Check that the term is well formed.
+	///
+	/// Returns the first ill-formed position in pre-order.
+	pub fn check_term(&self, term: &Term<F>) -> Result<(), SignatureError<F>> {
+		for (position, sub) in term.pre_order().with_positions() {
+			self.check_symbol(sub.symbol(), sub.sub_terms().len(), &position)?
+		}
+
+		Ok(())
+	}
+
+	/// Check that the pattern is well formed.
+	///
+	/// Returns the first ill-formed position in pre-order.
+	pub fn check_pattern<X>(&self, pattern: &Pattern<F, X>) -> Result<(), SignatureError<F>> {
+		for (position, sub) in pattern.pre_order().with_positions() {
+			if let PatternKind::Cons(f, subs) = sub.kind() {
+				self.check_symbol(f, subs.len(), &position)?
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Build a term, checking the arity of its root symbol.
+	///
+	/// The sub-terms are supposed to be well formed already.
+	pub fn term(&self, f: F, subs: Vec<Term<F>>) -> Result<Term<F>, SignatureError<F>> {
+		self.check_symbol(&f, subs.len(), &Position::root())?;
+		Ok(Term::new(f, subs))
+	}
+
+	/// Build a pattern, checking the arity of its root symbol.
+	///
+	/// The sub-patterns are supposed to be well formed already.
+	pub fn pattern<X: Clone>(&self, f: F, subs: Vec<Pattern<F, X>>) -> Result<Pattern<F, X>, SignatureError<F>> {
+		self.check_symbol(&f, subs.len(), &Position::root())?;
+		Ok(Pattern::cons(f, subs))
+	}
+}
+
+impl<F: Clone + Hash + Eq> Default for Signature<F> {
+	fn default() -> Signature<F> {
+		Signature::new()
+	}
+}
+
+impl<F: Clone> Clone for Signature<F> {
+	fn clone(&self) -> Signature<F> {
+		Signature {
+			symbols: self.symbols.clone(),
+			map: self.map.clone()
+		}
+	}
+}
+
+impl<F: fmt::Debug> fmt::Debug for Signature<F> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_map().entries(self.symbols.iter().map(|(g, arity)| (g, arity))).finish()
+	}
+}
+
+impl<F: Clone + Hash + Eq> std::iter::FromIterator<(F, usize)> for Signature<F> {
+	fn from_iter<I: IntoIterator<Item = (F, usize)>>(iter: I) -> Signature<F> {
+		let mut signature = Signature::new();
+		for (f, arity) in iter {
+			signature.add(f, arity);
+		}
+
+		signature
+	}
+}
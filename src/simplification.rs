@@ -0,0 +1,420 @@
+use std::cmp::Ordering;
+use crate::{Pattern, PatternKind};
+
+/// Result of the comparison of two patterns by a (partial) reduction order.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Comparison {
+	Greater,
+	Equal,
+	Less,
+	Incomparable
+}
+
+impl Comparison {
+	pub fn reverse(self) -> Comparison {
+		match self {
+			Comparison::Greater => Comparison::Less,
+			Comparison::Less => Comparison::Greater,
+			c => c
+		}
+	}
+}
+
+impl From<Ordering> for Comparison {
+	fn from(ord: Ordering) -> Comparison {
+		match ord {
+			Ordering::Greater => Comparison::Greater,
+			Ordering::Equal => Comparison::Equal,
+			Ordering::Less => Comparison::Less
+		}
+	}
+}
+
+/// A (partial) precedence on symbols.
+pub trait Precedence<F> {
+	/// Compare two symbols, or return `None` if they are incomparable.
+	fn compare(&self, f: &F, g: &F) -> Option<Ordering>;
+}
+
+impl<F, P: Fn(&F, &F) -> Option<Ordering>> Precedence<F> for P {
+	fn compare(&self, f: &F, g: &F) -> Option<Ordering> {
+		(*self)(f, g)
+	}
+}
+
+/// The total precedence given by the `Ord` implementation of the symbols.
+pub struct OrdPrecedence;
+
+impl<F: Ord> Precedence<F> for OrdPrecedence {
+	fn compare(&self, f: &F, g: &F) -> Option<Ordering> {
+		Some(f.cmp(g))
+	}
+}
+
+/// A reduction order on patterns.
+///
+/// Variables are universally quantified: `s > t` means that every instance of `s` is greater than the same instance of `t`.
+/// In particular, `s > t` requires every variable of `t` to occur in `s`.
+pub trait ReductionOrder<F, X> {
+	fn compare(&self, a: &Pattern<F, X>, b: &Pattern<F, X>) -> Comparison;
+
+	fn greater(&self, a: &Pattern<F, X>, b: &Pattern<F, X>) -> bool {
+		self.compare(a, b) == Comparison::Greater
+	}
+}
+
+fn occurs<F, X: PartialEq>(x: &X, p: &Pattern<F, X>) -> bool {
+	p.variable_occurrences().any(|y| x == y)
+}
+
+fn is_greater(ord: Option<Ordering>) -> bool {
+	ord == Some(Ordering::Greater)
+}
+
+/// Lexicographic path order.
+pub struct Lpo<P> {
+	precedence: P
+}
+
+impl<P> Lpo<P> {
+	pub fn new(precedence: P) -> Lpo<P> {
+		Lpo {
+			precedence
+		}
+	}
+
+	fn gt<F: PartialEq, X: PartialEq>(&self, s: &Pattern<F, X>, t: &Pattern<F, X>) -> bool where P: Precedence<F> {
+		match (s.kind(), t.kind()) {
+			(PatternKind::Var(_), _) => false,
+			(PatternKind::Cons(_, _), PatternKind::Var(x)) => occurs(x, s),
+			(PatternKind::Cons(f, subs_s), PatternKind::Cons(g, subs_t)) => {
+				if subs_s.iter().any(|si| si == t || self.gt(si, t)) {
+					return true
+				}
+
+				if f == g && subs_s.len() == subs_t.len() {
+					subs_t.iter().all(|tj| self.gt(s, tj)) && self.lex_gt(subs_s, subs_t)
+				} else {
+					is_greater(self.precedence.compare(f, g)) && subs_t.iter().all(|tj| self.gt(s, tj))
+				}
+			}
+		}
+	}
+
+	fn lex_gt<F: PartialEq, X: PartialEq>(&self, a: &[Pattern<F, X>], b: &[Pattern<F, X>]) -> bool where P: Precedence<F> {
+		for (ai, bi) in a.iter().zip(b.iter()) {
+			if ai != bi {
+				return self.gt(ai, bi)
+			}
+		}
+
+		false
+	}
+}
+
+impl<F: PartialEq, X: PartialEq, P: Precedence<F>> ReductionOrder<F, X> for Lpo<P> {
+	fn compare(&self, a: &Pattern<F, X>, b: &Pattern<F, X>) -> Comparison {
+		if a == b {
+			Comparison::Equal
+		} else if self.gt(a, b) {
+			Comparison::Greater
+		} else if self.gt(b, a) {
+			Comparison::Less
+		} else {
+			Comparison::Incomparable
+		}
+	}
+}
+
+/// Status of a symbol in the recursive path order.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Status {
+	/// Arguments are compared lexicographically, from left to right.
+	Lexicographic,
+
+	/// Arguments are compared as multisets.
+	Multiset
+}
+
+/// Recursive path order, where each symbol has a lexicographic or multiset status.
+///
+/// With only multiset statuses, this is the multiset path order.
+/// With only lexicographic statuses, this is the lexicographic path order.
+/// Patterns are equal for this order if they are equal up to the permutation of arguments of multiset status symbols.
+pub struct Rpo<P, S> {
+	precedence: P,
+	status: S
+}
+
+impl<P, S> Rpo<P, S> {
+	pub fn new(precedence: P, status: S) -> Rpo<P, S> {
+		Rpo {
+			precedence,
+			status
+		}
+	}
+}
+
+impl<P, F> Rpo<P, fn(&F) -> Status> {
+	/// Multiset path order: every symbol has the multiset status.
+	pub fn multiset(precedence: P) -> Rpo<P, fn(&F) -> Status> {
+		fn multiset<G>(_f: &G) -> Status {
+			Status::Multiset
+		}
+
+		Rpo {
+			precedence,
+			status: multiset::<F>
+		}
+	}
+}
+
+impl<P, S> Rpo<P, S> {
+	/// Equality up to permutation of the arguments of multiset status symbols.
+	fn equivalent<F: PartialEq, X: PartialEq>(&self, s: &Pattern<F, X>, t: &Pattern<F, X>) -> bool where S: Fn(&F) -> Status {
+		match (s.kind(), t.kind()) {
+			(PatternKind::Var(x), PatternKind::Var(y)) => x == y,
+			(PatternKind::Cons(f, subs_s), PatternKind::Cons(g, subs_t)) if f == g && subs_s.len() == subs_t.len() => {
+				match (self.status)(f) {
+					Status::Lexicographic => subs_s.iter().zip(subs_t.iter()).all(|(a, b)| self.equivalent(a, b)),
+					Status::Multiset => {
+						let mut remaining: Vec<&Pattern<F, X>> = subs_t.iter().collect();
+						for a in subs_s.iter() {
+							match remaining.iter().position(|b| self.equivalent(a, b)) {
+								Some(i) => {
+									remaining.swap_remove(i);
+								},
+								None => return false
+							}
+						}
+						true
+					}
+				}
+			},
+			_ => false
+		}
+	}
+
+	fn gt<F: PartialEq, X: PartialEq>(&self, s: &Pattern<F, X>, t: &Pattern<F, X>) -> bool where P: Precedence<F>, S: Fn(&F) -> Status {
+		match (s.kind(), t.kind()) {
+			(PatternKind::Var(_), _) => false,
+			(PatternKind::Cons(_, _), PatternKind::Var(x)) => occurs(x, s),
+			(PatternKind::Cons(f, subs_s), PatternKind::Cons(g, subs_t)) => {
+				if subs_s.iter().any(|si| self.equivalent(si, t) || self.gt(si, t)) {
+					return true
+				}
+
+				if f == g {
+					match (self.status)(f) {
+						Status::Lexicographic => {
+							subs_s.len() == subs_t.len() && subs_t.iter().all(|tj| self.gt(s, tj)) && self.lex_gt(subs_s, subs_t)
+						},
+						Status::Multiset => self.multiset_gt(subs_s, subs_t)
+					}
+				} else {
+					is_greater(self.precedence.compare(f, g)) && subs_t.iter().all(|tj| self.gt(s, tj))
+				}
+			}
+		}
+	}
+
+	fn lex_gt<F: PartialEq, X: PartialEq>(&self, a: &[Pattern<F, X>], b: &[Pattern<F, X>]) -> bool where P: Precedence<F>, S: Fn(&F) -> Status {
+		for (ai, bi) in a.iter().zip(b.iter()) {
+			if !self.equivalent(ai, bi) {
+				return self.gt(ai, bi)
+			}
+		}
+
+		false
+	}
+
+	/// Multiset extension of the order.
+	fn multiset_gt<F: PartialEq, X: PartialEq>(&self, a: &[Pattern<F, X>], b: &[Pattern<F, X>]) -> bool where P: Precedence<F>, S: Fn(&F) -> Status {
+		// Remove common elements.
+		let mut a: Vec<&Pattern<F, X>> = a.iter().collect();
+		let mut b_remaining = Vec::new();
+		for bi in b.iter() {
+			match a.iter().position(|ai| self.equivalent(ai, bi)) {
+				Some(i) => {
+					a.swap_remove(i);
+				},
+				None => b_remaining.push(bi)
+			}
+		}
+
+		!a.is_empty() && b_remaining.iter().all(|bi| a.iter().any(|ai| self.gt(ai, bi)))
+	}
+}
+
+impl<F: PartialEq, X: PartialEq, P: Precedence<F>, S: Fn(&F) -> Status> ReductionOrder<F, X> for Rpo<P, S> {
+	fn compare(&self, a: &Pattern<F, X>, b: &Pattern<F, X>) -> Comparison {
+		if self.equivalent(a, b) {
+			Comparison::Equal
+		} else if self.gt(a, b) {
+			Comparison::Greater
+		} else if self.gt(b, a) {
+			Comparison::Less
+		} else {
+			Comparison::Incomparable
+		}
+	}
+}
+
+/// Knuth-Bendix order.
+///
+/// Every symbol has a weight given by the weight function, and every variable has the weight `variable_weight`.
+/// For the order to be well-founded, the variable weight must be positive,
+/// constants must weight at least the variable weight,
+/// and a unary symbol of weight 0 must be greater than every other symbol in the precedence.
+/// This is not checked.
+pub struct Kbo<P, W> {
+	precedence: P,
+	weight: W,
+	variable_weight: usize
+}
+
+impl<P, W> Kbo<P, W> {
+	pub fn new(precedence: P, weight: W, variable_weight: usize) -> Kbo<P, W> {
+		Kbo {
+			precedence,
+			weight,
+			variable_weight
+		}
+	}
+
+	/// Weight of a pattern.
+	pub fn weight_of<F, X>(&self, p: &Pattern<F, X>) -> usize where W: Fn(&F) -> usize {
+		p.pre_order().map(|sub| match sub.kind() {
+			PatternKind::Cons(f, _) => (self.weight)(f),
+			PatternKind::Var(_) => self.variable_weight
+		}).sum()
+	}
+
+	/// Annotate the pattern with the weight and variable occurrences of each sub-pattern, bottom-up.
+	fn weighted<'a, F, X: PartialEq>(&self, p: &'a Pattern<F, X>) -> Weighted<'a, F, X> where W: Fn(&F) -> usize {
+		match p.kind() {
+			PatternKind::Var(x) => Weighted {
+				pattern: p,
+				weight: self.variable_weight,
+				variables: vec![(x, 1)],
+				subs: Vec::new()
+			},
+			PatternKind::Cons(f, subs) => {
+				let subs: Vec<_> = subs.iter().map(|sub| self.weighted(sub)).collect();
+				let mut weight = (self.weight)(f);
+				let mut variables: Vec<(&X, usize)> = Vec::new();
+				for sub in &subs {
+					weight += sub.weight;
+					for (x, n) in &sub.variables {
+						match variables.iter_mut().find(|(y, _)| y == x) {
+							Some((_, m)) => *m += n,
+							None => variables.push((x, *n))
+						}
+					}
+				}
+
+				Weighted {
+					pattern: p,
+					weight,
+					variables,
+					subs
+				}
+			}
+		}
+	}
+
+	fn gt<F: PartialEq, X: PartialEq>(&self, s: &Weighted<F, X>, t: &Weighted<F, X>) -> bool where P: Precedence<F> {
+		// Each variable must occur at least as often in `s` as in `t`.
+		if !t.variables.iter().all(|(x, n)| s.occurrences(x) >= *n) {
+			return false
+		}
+
+		if s.weight != t.weight {
+			return s.weight > t.weight
+		}
+
+		match (s.pattern.kind(), t.pattern.kind()) {
+			(PatternKind::Var(_), _) => false,
+			(PatternKind::Cons(_, _), PatternKind::Var(x)) => {
+				// `s` is of the form `f(f(...f(x)))`.
+				let mut p = s.pattern;
+				loop {
+					match p.kind() {
+						PatternKind::Cons(_, subs) if subs.len() == 1 => p = &subs[0],
+						PatternKind::Var(y) => return x == y,
+						_ => return false
+					}
+				}
+			},
+			(PatternKind::Cons(f, subs_s), PatternKind::Cons(g, subs_t)) => {
+				if f == g && subs_s.len() == subs_t.len() {
+					for (a, b) in s.subs.iter().zip(t.subs.iter()) {
+						if a.pattern != b.pattern {
+							return self.gt(a, b)
+						}
+					}
+					false
+				} else {
+					is_greater(self.precedence.compare(f, g))
+				}
+			}
+		}
+	}
+}
+
+/// A pattern with its weight and variable occurrences, and the same for its sub-patterns.
+struct Weighted<'a, F, X> {
+	pattern: &'a Pattern<F, X>,
+	weight: usize,
+	variables: Vec<(&'a X, usize)>,
+	subs: Vec<Weighted<'a, F, X>>
+}
+
+impl<'a, F, X: PartialEq> Weighted<'a, F, X> {
+	/// Number of occurrences of the given variable.
+	fn occurrences(&self, x: &X) -> usize {
+		self.variables.iter().find(|(y, _)| *y == x).map(|(_, n)| *n).unwrap_or(0)
+	}
+}
+
+impl<F: PartialEq, X: PartialEq, P: Precedence<F>, W: Fn(&F) -> usize> ReductionOrder<F, X> for Kbo<P, W> {
+	fn compare(&self, a: &Pattern<F, X>, b: &Pattern<F, X>) -> Comparison {
+		if a == b {
+			return Comparison::Equal
+		}
+
+		let a = self.weighted(a);
+		let b = self.weighted(b);
+		if self.gt(&a, &b) {
+			Comparison::Greater
+		} else if self.gt(&b, &a) {
+			Comparison::Less
+		} else {
+			Comparison::Incomparable
+		}
+	}
+}
+
+impl<F: PartialEq, X: PartialEq> Pattern<F, X> {
+	/// Check if the given pattern is homeomorphically embedded in this pattern.
+	///
+	/// This is the case if `other` can be obtained by removing symbols from `self`.
+	pub fn embeds(&self, other: &Pattern<F, X>) -> bool {
+		match (self.kind(), other.kind()) {
+			(PatternKind::Var(x), PatternKind::Var(y)) => x == y,
+			(PatternKind::Var(_), _) => false,
+			(PatternKind::Cons(f, subs), _) => {
+				if subs.iter().any(|sub| sub.embeds(other)) {
+					return true
+				}
+
+				match other.kind() {
+					PatternKind::Cons(g, other_subs) => {
+						f == g && subs.len() == other_subs.len() && subs.iter().zip(other_subs.iter()).all(|(a, b)| a.embeds(b))
+					},
+					PatternKind::Var(_) => false
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,261 @@
+use std::fmt;
+use std::hash::Hash;
+use std::collections::HashMap;
+use crate::{Term, Pattern, PatternKind, Position};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SortErrorKind<F, S> {
+	/// The symbol is not declared in the signature.
+	UnknownSymbol(F),
+
+	/// The symbol is applied to the wrong number of arguments.
+	///
+	/// Gives the symbol, its declared arity and the actual number of arguments.
+	Arity(F, usize, usize),
+
+	/// The sort found is not a subsort of the expected sort.
+	///
+	/// Gives the expected sort, then the sort found.
+	Mismatch(S, S),
+
+	/// The variable occurs at positions with incompatible sorts.
+	///
+	/// Gives the sort inferred from the previous occurrences, then the sort expected at this position.
+	VariableConflict(S, S)
+}
+
+/// Sort checking error, located by its position in the term or pattern.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SortError<F, S> {
+	pub position: Position,
+	pub kind: SortErrorKind<F, S>
+}
+
+impl<F: fmt::Display, S: fmt::Display> fmt::Display for SortErrorKind<F, S> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			SortErrorKind::UnknownSymbol(g) => write!(f, "unknown symbol {}", g),
+			SortErrorKind::Arity(g, expected, found) => write!(f, "symbol {} expects {} argument(s), but {} are given", g, expected, found),
+			SortErrorKind::Mismatch(expected, found) => write!(f, "expected sort {}, found {}", expected, found),
+			SortErrorKind::VariableConflict(previous, expected) => write!(f, "variable of sort {} used with sort {}", previous, expected)
+		}
+	}
+}
+
+impl<F: fmt::Display, S: fmt::Display> fmt::Display for SortError<F, S> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} at position {}", self.kind, self.position)
+	}
+}
+
+impl<F: fmt::Debug + fmt::Display, S: fmt::Debug + fmt::Display> std::error::Error for SortError<F, S> {}
+
+/// Declaration of a symbol in a sorted signature.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SortDeclaration<S> {
+	pub arguments: Vec<S>,
+	pub result: S
+}
+
+/// A many-sorted signature, with optional subsorts.
+///
+/// Each symbol declares the sort of its arguments and of its result.
+/// A term of sort `s` can be used where a super-sort of `s` is expected.
+pub struct SortedSignature<F, S> {
+	symbols: Vec<(F, SortDeclaration<S>)>,
+	map: HashMap<F, usize>,
+	subsorts: Vec<(S, S)>
+}
+
+impl<F: Clone + Hash + Eq, S: Clone + Eq> SortedSignature<F, S> {
+	pub fn new() -> SortedSignature<F, S> {
+		SortedSignature {
+			symbols: Vec::new(),
+			map: HashMap::new(),
+			subsorts: Vec::new()
+		}
+	}
+
+	/// Declare a symbol with the given argument sorts and result sort.
+	///
+	/// If the symbol was already declared, its declaration is replaced and the previous one is returned.
+	pub fn add(&mut self, f: F, arguments: Vec<S>, result: S) -> Option<SortDeclaration<S>> {
+		let declaration = SortDeclaration {
+			arguments,
+			result
+		};
+
+		match self.map.get(&f) {
+			Some(i) => Some(std::mem::replace(&mut self.symbols[*i].1, declaration)),
+			None => {
+				self.map.insert(f.clone(), self.symbols.len());
+				self.symbols.push((f, declaration));
+				None
+			}
+		}
+	}
+
+	/// Declare `sub` as a subsort of `sup`.
+	pub fn add_subsort(&mut self, sub: S, sup: S) {
+		self.subsorts.push((sub, sup))
+	}
+
+	pub fn declaration(&self, f: &F) -> Option<&SortDeclaration<S>> {
+		self.map.get(f).map(|i| &self.symbols[*i].1)
+	}
+
+	/// Iterate over the declared symbols, in declaration order.
+	pub fn iter(&self) -> impl Iterator<Item = (&F, &SortDeclaration<S>)> {
+		self.symbols.iter().map(|(f, declaration)| (f, declaration))
+	}
+
+	pub fn len(&self) -> usize {
+		self.symbols.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.symbols.is_empty()
+	}
+
+	/// Check if `a` is a subsort of (or equal to) `b`.
+	pub fn is_subsort(&self, a: &S, b: &S) -> bool {
+		let mut visited = vec![a];
+		let mut stack = vec![a];
+		while let Some(s) = stack.pop() {
+			if s == b {
+				return true
+			}
+
+			for (sub, sup) in self.subsorts.iter() {
+				if sub == s && !visited.contains(&sup) {
+					visited.push(sup);
+					stack.push(sup)
+				}
+			}
+		}
+
+		false
+	}
+
+	fn check_symbol(&self, f: &F, arguments: usize, position: &Position) -> Result<&SortDeclaration<S>, SortError<F, S>> {
+		let kind = match self.declaration(f) {
+			Some(declaration) if declaration.arguments.len() == arguments => return Ok(declaration),
+			Some(declaration) => SortErrorKind::Arity(f.clone(), declaration.arguments.len(), arguments),
+			None => SortErrorKind::UnknownSymbol(f.clone())
+		};
+
+		Err(SortError {
+			position: position.clone(),
+			kind
+		})
+	}
+
+	fn check_subsort(&self, found: &S, expected: &S, position: &Position) -> Result<(), SortError<F, S>> {
+		if self.is_subsort(found, expected) {
+			Ok(())
+		} else {
+			Err(SortError {
+				position: position.clone(),
+				kind: SortErrorKind::Mismatch(expected.clone(), found.clone())
+			})
+		}
+	}
+
+	/// Compute the sort of the given term, checking that it is well sorted.
+	pub fn sort_of_term(&self, term: &Term<F>) -> Result<S, SortError<F, S>> {
+		let mut sorts: Vec<(Position, S)> = Vec::new();
+		for (position, sub) in term.post_order().with_positions() {
+			let declaration = self.check_symbol(sub.symbol(), sub.sub_terms().len(), &position)?;
+			let arguments = sorts.split_off(sorts.len() - declaration.arguments.len());
+			for ((sub_position, found), expected) in arguments.iter().zip(declaration.arguments.iter()) {
+				self.check_subsort(found, expected, sub_position)?
+			}
+			sorts.push((position, declaration.result.clone()))
+		}
+
+		Ok(sorts.pop().unwrap().1)
+	}
+
+	/// Check that the term is well sorted, with a subsort of the expected sort.
+	pub fn check_term(&self, term: &Term<F>, expected: &S) -> Result<(), SortError<F, S>> {
+		let sort = self.sort_of_term(term)?;
+		self.check_subsort(&sort, expected, &Position::root())
+	}
+
+	/// Check that the pattern is well sorted, and infer the sort of its variables.
+	///
+	/// The sort of a variable is inferred from the positions where it occurs.
+	/// When it occurs at positions of different sorts, its sort is the smallest of them,
+	/// which must be a subsort of the others.
+	/// If `expected` is given, the pattern must have a subsort of it.
+	/// A pattern reduced to a variable gets the expected sort, if any.
+	pub fn check_pattern<X: Clone + PartialEq>(&self, pattern: &Pattern<F, X>, expected: Option<&S>) -> Result<Vec<(X, S)>, SortError<F, S>> {
+		let mut variables: Vec<(X, S)> = Vec::new();
+		let mut stack = vec![(pattern, Position::root(), expected.cloned())];
+		while let Some((sub, position, expected)) = stack.pop() {
+			match sub.kind() {
+				PatternKind::Cons(f, subs) => {
+					let declaration = self.check_symbol(f, subs.len(), &position)?;
+					if let Some(expected) = &expected {
+						self.check_subsort(&declaration.result, expected, &position)?
+					}
+
+					for (i, (sub_sub, sort)) in subs.iter().zip(declaration.arguments.iter()).enumerate().rev() {
+						stack.push((sub_sub, position.child(i), Some(sort.clone())))
+					}
+				},
+				PatternKind::Var(x) => {
+					if let Some(expected) = expected {
+						match variables.iter_mut().find(|(y, _)| y == x) {
+							Some((_, sort)) => {
+								if self.is_subsort(&expected, sort) {
+									*sort = expected
+								} else if !self.is_subsort(sort, &expected) {
+									return Err(SortError {
+										position,
+										kind: SortErrorKind::VariableConflict(sort.clone(), expected)
+									})
+								}
+							},
+							None => variables.push((x.clone(), expected))
+						}
+					}
+				}
+			}
+		}
+
+		Ok(variables)
+	}
+
+	/// The sort of the given pattern, if it is not a variable.
+	///
+	/// This does not check that the pattern is well sorted.
+	pub fn sort_of_pattern<X>(&self, pattern: &Pattern<F, X>) -> Option<&S> {
+		pattern.symbol().and_then(|f| self.declaration(f)).map(|declaration| &declaration.result)
+	}
+}
+
+impl<F: Clone + Hash + Eq, S: Clone + Eq> Default for SortedSignature<F, S> {
+	fn default() -> SortedSignature<F, S> {
+		SortedSignature::new()
+	}
+}
+
+impl<F: Clone, S: Clone> Clone for SortedSignature<F, S> {
+	fn clone(&self) -> SortedSignature<F, S> {
+		SortedSignature {
+			symbols: self.symbols.clone(),
+			map: self.map.clone(),
+			subsorts: self.subsorts.clone()
+		}
+	}
+}
+
+impl<F: fmt::Debug, S: fmt::Debug> fmt::Debug for SortedSignature<F, S> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("SortedSignature")
+			.field("symbols", &self.symbols)
+			.field("subsorts", &self.subsorts)
+			.finish()
+	}
+}
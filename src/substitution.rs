@@ -0,0 +1,235 @@
+use std::fmt;
+use crate::{Term, Pattern, PatternKind, PatternLike, PatternLikeKind};
+
+/// A substitution, mapping variables to patterns.
+///
+/// Bindings are kept in insertion order.
+pub struct Substitution<F, X> {
+	bindings: Vec<(X, Pattern<F, X>)>
+}
+
+impl<F, X> Substitution<F, X> {
+	/// Create a new empty substitution.
+	pub fn new() -> Substitution<F, X> {
+		Substitution {
+			bindings: Vec::new()
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.bindings.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.bindings.is_empty()
+	}
+
+	/// Get the pattern bound to the given variable, if any.
+	pub fn get(&self, x: &X) -> Option<&Pattern<F, X>> where X: PartialEq {
+		self.bindings.iter().find(|(y, _)| y == x).map(|(_, p)| p)
+	}
+
+	/// Bind the given variable.
+	///
+	/// Returns the previous binding of the variable, if any.
+	pub fn bind(&mut self, x: X, pattern: Pattern<F, X>) -> Option<Pattern<F, X>> where X: PartialEq {
+		for (y, p) in self.bindings.iter_mut() {
+			if *y == x {
+				return Some(std::mem::replace(p, pattern))
+			}
+		}
+
+		self.bindings.push((x, pattern));
+		None
+	}
+
+	/// Remove the binding of the given variable.
+	pub fn remove(&mut self, x: &X) -> Option<Pattern<F, X>> where X: PartialEq {
+		let i = self.bindings.iter().position(|(y, _)| y == x)?;
+		Some(self.bindings.remove(i).1)
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = (&X, &Pattern<F, X>)> {
+		self.bindings.iter().map(|(x, p)| (x, p))
+	}
+
+	/// Variables bound by the substitution.
+	pub fn domain(&self) -> impl Iterator<Item = &X> {
+		self.bindings.iter().map(|(x, _)| x)
+	}
+
+	/// Patterns bound to the variables of the domain.
+	pub fn range(&self) -> impl Iterator<Item = &Pattern<F, X>> {
+		self.bindings.iter().map(|(_, p)| p)
+	}
+
+	/// Apply the substitution to a pattern (or any pattern-like value).
+	///
+	/// Each variable is replaced once. Use [`resolve`](Substitution::resolve) with triangular substitutions.
+	pub fn apply<T: PatternLike<F, X>>(&self, p: &T) -> Pattern<F, X> where F: Clone, X: Clone + PartialEq {
+		match p.kind() {
+			PatternLikeKind::Var(x) => match self.get(x) {
+				Some(q) => q.clone(),
+				None => Pattern::var(x.clone())
+			},
+			PatternLikeKind::Cons(f, subs) => {
+				Pattern::cons(f.clone(), subs.iter().map(|sub| self.apply(sub)).collect())
+			}
+		}
+	}
+
+	/// Apply the substitution to a pattern, and return the resulting term if it is ground.
+	pub fn instantiate<T: PatternLike<F, X>>(&self, p: &T) -> Option<Term<F>> where F: Clone, X: Clone + PartialEq {
+		match p.kind() {
+			PatternLikeKind::Var(x) => self.get(x)?.as_term(),
+			PatternLikeKind::Cons(f, subs) => {
+				let mut sub_terms = Vec::with_capacity(subs.len());
+				for sub in subs.iter() {
+					sub_terms.push(self.instantiate(sub)?)
+				}
+
+				Some(Term::new(f.clone(), sub_terms))
+			}
+		}
+	}
+
+	/// Apply the substitution to a pattern until no variable of the domain remains.
+	///
+	/// This is how triangular substitutions (such as the ones built by
+	/// [`Pattern::unify_with`]) are applied. The substitution must not be cyclic,
+	/// but trivial bindings `x -> x` are allowed.
+	pub fn resolve<T: PatternLike<F, X>>(&self, p: &T) -> Pattern<F, X> where F: Clone, X: Clone + PartialEq {
+		match p.kind() {
+			PatternLikeKind::Var(x) => match self.get(x) {
+				Some(q) => match q.kind() {
+					// Trivial binding `x -> x`.
+					PatternKind::Var(y) if y == x => q.clone(),
+					_ => self.resolve(q)
+				},
+				None => Pattern::var(x.clone())
+			},
+			PatternLikeKind::Cons(f, subs) => {
+				Pattern::cons(f.clone(), subs.iter().map(|sub| self.resolve(sub)).collect())
+			}
+		}
+	}
+
+	/// Composition of substitutions.
+	///
+	/// Applying the result is the same as applying `self`, then `other`.
+	pub fn compose(&self, other: &Substitution<F, X>) -> Substitution<F, X> where F: Clone + PartialEq, X: Clone + PartialEq {
+		let mut result = Substitution::new();
+		for (x, p) in self.iter() {
+			result.bind_non_trivial(x.clone(), other.apply(p));
+		}
+
+		for (x, p) in other.iter() {
+			if self.get(x).is_none() {
+				result.bind_non_trivial(x.clone(), p.clone());
+			}
+		}
+
+		result
+	}
+
+	/// Restriction of the substitution to the given variables.
+	pub fn restrict<'a, I: IntoIterator<Item = &'a X>>(&self, variables: I) -> Substitution<F, X> where F: Clone + 'a, X: Clone + PartialEq + 'a {
+		let mut result = Substitution::new();
+		for x in variables {
+			if let Some(p) = self.get(x) {
+				result.bind(x.clone(), p.clone());
+			}
+		}
+
+		result
+	}
+
+	/// Check if applying the substitution twice is the same as applying it once.
+	///
+	/// This is the case when no variable of the domain occurs in the range.
+	pub fn is_idempotent(&self) -> bool where X: PartialEq {
+		self.range().all(|p| p.variables().all(|x| self.get(x).is_none()))
+	}
+
+	/// Compute the idempotent substitution equivalent to this triangular substitution.
+	///
+	/// Trivial bindings `x -> x` are removed. The substitution must not be cyclic.
+	pub fn normalized(&self) -> Substitution<F, X> where F: Clone + PartialEq, X: Clone + PartialEq {
+		let mut result = Substitution::new();
+		for (x, p) in self.iter() {
+			result.bind_non_trivial(x.clone(), self.resolve(p));
+		}
+
+		result
+	}
+
+	fn bind_non_trivial(&mut self, x: X, p: Pattern<F, X>) where X: PartialEq {
+		match p.kind() {
+			PatternKind::Var(y) if *y == x => (),
+			_ => {
+				self.bind(x, p);
+			}
+		}
+	}
+}
+
+impl<F, X> Default for Substitution<F, X> {
+	fn default() -> Substitution<F, X> {
+		Substitution::new()
+	}
+}
+
+impl<F: Clone, X: Clone> Clone for Substitution<F, X> {
+	fn clone(&self) -> Substitution<F, X> {
+		Substitution {
+			bindings: self.bindings.clone()
+		}
+	}
+}
+
+impl<F: PartialEq, X: PartialEq> PartialEq for Substitution<F, X> {
+	fn eq(&self, other: &Substitution<F, X>) -> bool {
+		self.len() == other.len() && self.bindings.iter().all(|(x, p)| other.get(x) == Some(p))
+	}
+}
+
+impl<F: Eq, X: Eq> Eq for Substitution<F, X> {}
+
+impl<F: fmt::Debug, X: fmt::Debug> fmt::Debug for Substitution<F, X> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_map().entries(self.iter()).finish()
+	}
+}
+
+impl<F: fmt::Display, X: fmt::Display> fmt::Display for Substitution<F, X> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{{")?;
+		for (i, (x, p)) in self.iter().enumerate() {
+			if i > 0 {
+				write!(f, ", ")?;
+			}
+			write!(f, "{} -> {}", x, p)?;
+		}
+		write!(f, "}}")
+	}
+}
+
+impl<F, X: PartialEq> std::iter::FromIterator<(X, Pattern<F, X>)> for Substitution<F, X> {
+	fn from_iter<I: IntoIterator<Item = (X, Pattern<F, X>)>>(iter: I) -> Substitution<F, X> {
+		let mut sigma = Substitution::new();
+		for (x, p) in iter {
+			sigma.bind(x, p);
+		}
+
+		sigma
+	}
+}
+
+impl<F, X> IntoIterator for Substitution<F, X> {
+	type Item = (X, Pattern<F, X>);
+	type IntoIter = std::vec::IntoIter<(X, Pattern<F, X>)>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.bindings.into_iter()
+	}
+}
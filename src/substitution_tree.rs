@@ -0,0 +1,363 @@
+use crate::{Pattern, PatternKind, Substitution, Index, reindex_from};
+use crate::discrimination::Retrieved;
+use crate::unification::unify_flexible;
+
+/// Variables of the patterns stored in the tree nodes.
+#[derive(Clone, PartialEq, Debug)]
+enum Slot<X> {
+	/// Internal variable, bound further down in the tree.
+	Indicator(usize),
+
+	/// Normalized variable of a stored pattern.
+	Normal(usize),
+
+	/// Variable of a query pattern.
+	Query(X)
+}
+
+type Bindings<F, X> = Vec<(usize, Pattern<F, Slot<X>>)>;
+
+struct Node<F, X, V> {
+	/// Bindings of the indicator variables resolved by this node.
+	bindings: Bindings<F, X>,
+
+	children: Vec<Node<F, X, V>>,
+
+	/// Stored patterns, if this node is a leaf.
+	///
+	/// They are variants of each other.
+	entries: Vec<(Pattern<F, X>, V)>
+}
+
+/// Substitution tree, indexing patterns by their common instantiations.
+///
+/// Each node binds some internal (indicator) variables,
+/// and composing the bindings along a branch gives the stored pattern for the indicator `*0`,
+/// with its variables normalized.
+/// Patterns sharing the same prefix of bindings share the same nodes.
+///
+/// It retrieves the stored patterns unifiable with a query pattern,
+/// the generalizations of a query pattern, or its instances.
+pub struct SubstitutionTree<F, X, V> {
+	/// Children of the root, where only `*0` is open.
+	roots: Vec<Node<F, X, V>>,
+
+	/// Next fresh indicator variable.
+	next_indicator: usize,
+
+	len: usize
+}
+
+/// Rename the variables of the pattern to normalized variables, in the order of their first occurrence.
+fn normalize<F: Clone, X: Clone + PartialEq>(pattern: &Pattern<F, X>) -> Pattern<F, Slot<X>> {
+	let (reindexed, _) = reindex_from(&[pattern], 0usize);
+	reindexed[0].map_variables(&|i| Pattern::var(Slot::Normal(*i)))
+}
+
+/// Match the pattern against a subject without indicator variables, by binding only the indicators of the pattern.
+fn match_indicators<F: Clone + PartialEq, X: Clone + PartialEq>(pattern: &Pattern<F, Slot<X>>, subject: &Pattern<F, Slot<X>>, rho: &mut Bindings<F, X>) -> bool {
+	match (pattern.kind(), subject.kind()) {
+		(PatternKind::Var(Slot::Indicator(i)), _) => {
+			match rho.iter().find(|(j, _)| i == j) {
+				Some((_, p)) => p == subject,
+				None => {
+					rho.push((*i, subject.clone()));
+					true
+				}
+			}
+		},
+		(PatternKind::Cons(f, subs), PatternKind::Cons(g, subject_subs)) if f == g && subs.len() == subject_subs.len() => {
+			subs.iter().zip(subject_subs.iter()).all(|(a, b)| match_indicators(a, b, rho))
+		},
+		_ => pattern == subject
+	}
+}
+
+/// If the node bindings generalize the given bindings, return the bindings that remain to be resolved below the node.
+fn match_bindings<F: Clone + PartialEq, X: Clone + PartialEq>(bindings: &Bindings<F, X>, rest: &Bindings<F, X>) -> Option<Bindings<F, X>> {
+	let mut rho = Vec::new();
+	for (i, t) in bindings {
+		let (_, s) = rest.iter().find(|(j, _)| i == j)?;
+		if !match_indicators(t, s, &mut rho) {
+			return None
+		}
+	}
+
+	rho.extend(rest.iter().filter(|(j, _)| bindings.iter().all(|(i, _)| i != j)).cloned());
+	Some(rho)
+}
+
+/// Compute a common generalization of the two patterns, introducing fresh indicators for the disagreements.
+fn generalize<F: Clone + PartialEq, X: Clone + PartialEq>(a: &Pattern<F, Slot<X>>, b: &Pattern<F, Slot<X>>, left: &mut Bindings<F, X>, right: &mut Bindings<F, X>, next: &mut usize) -> Pattern<F, Slot<X>> {
+	if a == b {
+		return a.clone()
+	}
+
+	if let (PatternKind::Cons(f, subs_a), PatternKind::Cons(g, subs_b)) = (a.kind(), b.kind()) {
+		if f == g && subs_a.len() == subs_b.len() {
+			let subs = subs_a.iter().zip(subs_b.iter()).map(|(sub_a, sub_b)| generalize(sub_a, sub_b, left, right, next)).collect();
+			return Pattern::cons(f.clone(), subs)
+		}
+	}
+
+	let k = *next;
+	*next = next.next();
+	left.push((k, a.clone()));
+	right.push((k, b.clone()));
+	Pattern::var(Slot::Indicator(k))
+}
+
+/// Replace the indicators bound by `bindings` in the pattern.
+fn substitute<F: Clone, X: Clone>(pattern: &Pattern<F, Slot<X>>, bindings: &Bindings<F, X>) -> Pattern<F, Slot<X>> {
+	pattern.map_variables(&|x| match x {
+		Slot::Indicator(i) => match bindings.iter().find(|(j, _)| i == j) {
+			Some((_, p)) => p.clone(),
+			None => Pattern::var(x.clone())
+		},
+		_ => Pattern::var(x.clone())
+	})
+}
+
+impl<F: Clone + PartialEq, X: Clone + PartialEq, V> Node<F, X, V> {
+	fn leaf(bindings: Bindings<F, X>, pattern: Pattern<F, X>, value: V) -> Node<F, X, V> {
+		Node {
+			bindings,
+			children: Vec::new(),
+			entries: vec![(pattern, value)]
+		}
+	}
+
+	fn is_empty(&self) -> bool {
+		self.children.is_empty() && self.entries.is_empty()
+	}
+
+	/// Merge the node with its only child.
+	fn merge(&mut self) {
+		let child = self.children.pop().unwrap();
+		let mut bindings: Bindings<F, X> = self.bindings.iter().map(|(i, t)| (*i, substitute(t, &child.bindings))).collect();
+		for (j, u) in child.bindings {
+			let introduced = self.bindings.iter().any(|(_, t)| t.variable_occurrences().any(|x| *x == Slot::Indicator(j)));
+			if !introduced {
+				bindings.push((j, u))
+			}
+		}
+
+		self.bindings = bindings;
+		self.children = child.children;
+		self.entries = child.entries
+	}
+}
+
+impl<F: Clone + PartialEq, X: Clone + PartialEq, V> SubstitutionTree<F, X, V> {
+	pub fn new() -> SubstitutionTree<F, X, V> {
+		SubstitutionTree {
+			roots: Vec::new(),
+			next_indicator: 1,
+			len: 0
+		}
+	}
+
+	/// Number of stored patterns.
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Insert a pattern with its value.
+	///
+	/// If the pattern was already in the tree, its value is replaced and the previous one is returned.
+	pub fn insert(&mut self, pattern: Pattern<F, X>, value: V) -> Option<V> {
+		let mut rest = vec![(0, normalize(&pattern))];
+
+		// Several children may generalize the pattern: look for it below each of them first.
+		if let Some(old) = Self::find_in(&mut self.roots, &rest, &pattern) {
+			return Some(std::mem::replace(old, value))
+		}
+
+		let mut children = &mut self.roots;
+		loop {
+			// Follow a child whose bindings generalize the pattern.
+			let found = children.iter().enumerate().find_map(|(i, child)| match_bindings(&child.bindings, &rest).map(|rho| (i, rho)));
+			if let Some((i, rho)) = found {
+				let child = &mut children[i];
+				if rho.is_empty() {
+					child.entries.push((pattern, value));
+					self.len += 1;
+					return None
+				}
+
+				children = &mut child.children;
+				rest = rho;
+				continue
+			}
+
+			// Split a child sharing a non-trivial generalization with the pattern.
+			for i in 0..children.len() {
+				let mut next = self.next_indicator;
+				let mut left = Vec::new();
+				let mut right = Vec::new();
+				let common: Bindings<F, X> = children[i].bindings.iter().map(|(j, t)| {
+					let s = &rest.iter().find(|(k, _)| j == k).unwrap().1;
+					(*j, generalize(t, s, &mut left, &mut right, &mut next))
+				}).collect();
+
+				if common.iter().any(|(_, t)| !matches!(t.kind(), PatternKind::Var(Slot::Indicator(_)))) {
+					self.next_indicator = next;
+					let mut old = children.remove(i);
+					right.extend(rest.into_iter().filter(|(j, _)| old.bindings.iter().all(|(k, _)| j != k)));
+					old.bindings = left;
+					children.insert(i, Node {
+						bindings: common,
+						children: vec![old, Node::leaf(right, pattern, value)],
+						entries: Vec::new()
+					});
+					self.len += 1;
+					return None
+				}
+			}
+
+			children.push(Node::leaf(rest, pattern, value));
+			self.len += 1;
+			return None
+		}
+	}
+
+	/// Find the value of a stored pattern, searching every child whose bindings generalize it.
+	fn find_in<'a>(children: &'a mut [Node<F, X, V>], rest: &Bindings<F, X>, pattern: &Pattern<F, X>) -> Option<&'a mut V> {
+		for child in children.iter_mut() {
+			if let Some(rho) = match_bindings(&child.bindings, rest) {
+				let value = if rho.is_empty() {
+					child.entries.iter_mut().find(|(p, _)| p == pattern).map(|(_, v)| v)
+				} else {
+					Self::find_in(&mut child.children, &rho, pattern)
+				};
+
+				if value.is_some() {
+					return value
+				}
+			}
+		}
+
+		None
+	}
+
+	/// Remove a pattern from the tree, and return its value.
+	///
+	/// Empty branches are removed, and nodes left with a single child are merged with it.
+	pub fn remove(&mut self, pattern: &Pattern<F, X>) -> Option<V> {
+		let rest = vec![(0, normalize(pattern))];
+		let value = Self::remove_from(&mut self.roots, &rest, pattern);
+		if value.is_some() {
+			self.len -= 1
+		}
+
+		value
+	}
+
+	fn remove_from(children: &mut Vec<Node<F, X, V>>, rest: &Bindings<F, X>, pattern: &Pattern<F, X>) -> Option<V> {
+		for i in 0..children.len() {
+			if let Some(rho) = match_bindings(&children[i].bindings, rest) {
+				let child = &mut children[i];
+				// Another child may also generalize the pattern: keep searching if it is not found below this one.
+				let value = if rho.is_empty() {
+					child.entries.iter().position(|(p, _)| p == pattern).map(|j| child.entries.remove(j).1)
+				} else {
+					Self::remove_from(&mut child.children, &rho, pattern)
+				};
+
+				if value.is_some() {
+					if child.is_empty() {
+						children.remove(i);
+					} else if child.children.len() == 1 && child.entries.is_empty() {
+						child.merge()
+					}
+
+					return value
+				}
+			}
+		}
+
+		None
+	}
+
+	/// Iterate over the stored patterns and their values.
+	pub fn iter(&self) -> impl Iterator<Item = (&Pattern<F, X>, &V)> {
+		let mut stack: Vec<&Node<F, X, V>> = self.roots.iter().collect();
+		let mut entries = Vec::new();
+		while let Some(node) = stack.pop() {
+			entries.extend(node.entries.iter().map(|(p, v)| (p, v)));
+			stack.extend(node.children.iter())
+		}
+
+		entries.into_iter()
+	}
+
+	/// Retrieve the stored patterns whose branch unifies with the query,
+	/// where only the variables satisfying `flexible` (and the indicators) can be bound.
+	fn retrieve<P: Fn(&Slot<X>) -> bool>(&self, query: &Pattern<F, X>, flexible: P) -> Vec<(&Pattern<F, X>, &V)> {
+		let query = query.map_variables(&|x| Pattern::var(Slot::Query(x.clone())));
+		let mut sigma = Substitution::new();
+		sigma.bind(Slot::Indicator(0), query);
+
+		let flexible = |x: &Slot<X>| matches!(x, Slot::Indicator(_)) || flexible(x);
+		let mut results = Vec::new();
+		let mut stack: Vec<_> = self.roots.iter().rev().map(|node| (node, sigma.clone())).collect();
+		while let Some((node, mut sigma)) = stack.pop() {
+			let unifies = node.bindings.iter().all(|(i, t)| {
+				unify_flexible(&Pattern::var(Slot::Indicator(*i)), t, &mut sigma, &flexible).is_ok()
+			});
+
+			if unifies {
+				results.extend(node.entries.iter().map(|(p, v)| (p, v)));
+				stack.extend(node.children.iter().rev().map(|child| (child, sigma.clone())))
+			}
+		}
+
+		results
+	}
+
+	/// Stored patterns unifiable with the given pattern.
+	///
+	/// The variables of the query and of the stored patterns are considered distinct.
+	pub fn unifiable(&self, pattern: &Pattern<F, X>) -> Vec<(&Pattern<F, X>, &V)> {
+		self.retrieve(pattern, |_| true)
+	}
+
+	/// Stored patterns that generalize the given pattern, with the matching substitution.
+	///
+	/// Variables of the query are treated as constants.
+	pub fn generalizations(&self, pattern: &Pattern<F, X>) -> Vec<Retrieved<'_, F, X, V>> {
+		self.retrieve(pattern, |x| matches!(x, Slot::Normal(_))).into_iter().filter_map(|(stored, value)| {
+			stored.matches_like(pattern).map(|substitution| Retrieved { pattern: stored, value, substitution })
+		}).collect()
+	}
+
+	/// Stored patterns that are instances of the given pattern, with the matching substitution.
+	///
+	/// Variables of the stored patterns are treated as constants.
+	pub fn instances(&self, pattern: &Pattern<F, X>) -> Vec<Retrieved<'_, F, X, V>> {
+		self.retrieve(pattern, |x| matches!(x, Slot::Query(_))).into_iter().filter_map(|(stored, value)| {
+			pattern.matches_like(stored).map(|substitution| Retrieved { pattern: stored, value, substitution })
+		}).collect()
+	}
+}
+
+impl<F: Clone + PartialEq, X: Clone + PartialEq, V> Default for SubstitutionTree<F, X, V> {
+	fn default() -> SubstitutionTree<F, X, V> {
+		SubstitutionTree::new()
+	}
+}
+
+impl<F: Clone + PartialEq, X: Clone + PartialEq, V> std::iter::FromIterator<(Pattern<F, X>, V)> for SubstitutionTree<F, X, V> {
+	fn from_iter<I: IntoIterator<Item = (Pattern<F, X>, V)>>(iter: I) -> SubstitutionTree<F, X, V> {
+		let mut tree = SubstitutionTree::new();
+		for (pattern, value) in iter {
+			tree.insert(pattern, value);
+		}
+
+		tree
+	}
+}
@@ -0,0 +1,517 @@
+use std::hash::Hash;
+use std::collections::HashMap;
+use crate::{Term, Pattern, PatternKind, Substitution};
+use crate::rewriting::{RewriteSystem, Equation};
+
+/// Equational axioms of a symbol.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Axioms {
+	/// `f(f(x, y), z) = f(x, f(y, z))`.
+	Associative,
+
+	/// `f(x, y) = f(y, x)`.
+	///
+	/// For other arities, the order of the arguments does not matter.
+	Commutative,
+
+	/// Both associative and commutative.
+	AssociativeCommutative
+}
+
+impl Axioms {
+	pub fn is_associative(self) -> bool {
+		self != Axioms::Commutative
+	}
+
+	pub fn is_commutative(self) -> bool {
+		self != Axioms::Associative
+	}
+}
+
+/// Variable bindings found during matching.
+type Bindings<F, X> = Vec<(X, Term<F>)>;
+
+/// Unification problem: equations to solve, and the substitution found so far.
+type Problem<F, X> = (Vec<Equation<F, X>>, Substitution<F, X>);
+
+/// Declaration of associative and commutative symbols.
+///
+/// Associative symbols are handled in their flattened (variadic) form,
+/// where nested applications of the symbol are merged into a single application:
+/// `f(f(a, b), c)` is flattened into `f(a, b, c)`.
+/// Every term and pattern given to the matching functions is flattened first.
+pub struct Theory<F> {
+	axioms: HashMap<F, Axioms>
+}
+
+impl<F: Clone + Eq + Hash> Theory<F> {
+	pub fn new() -> Theory<F> {
+		Theory {
+			axioms: HashMap::new()
+		}
+	}
+
+	/// Declare the axioms of a symbol.
+	///
+	/// Returns the previous declaration, if any.
+	pub fn declare(&mut self, f: F, axioms: Axioms) -> Option<Axioms> {
+		self.axioms.insert(f, axioms)
+	}
+
+	/// Axioms of the given symbol, if any.
+	pub fn axioms(&self, f: &F) -> Option<Axioms> {
+		self.axioms.get(f).cloned()
+	}
+
+	pub fn is_associative(&self, f: &F) -> bool {
+		self.axioms(f).map(Axioms::is_associative).unwrap_or(false)
+	}
+
+	pub fn is_commutative(&self, f: &F) -> bool {
+		self.axioms(f).map(Axioms::is_commutative).unwrap_or(false)
+	}
+
+	/// Flatten nested applications of associative symbols.
+	pub fn flatten_term(&self, term: &Term<F>) -> Term<F> {
+		let f = term.symbol();
+		let mut subs = Vec::with_capacity(term.sub_terms().len());
+		for sub in term.sub_terms().iter() {
+			let sub = self.flatten_term(sub);
+			if sub.symbol() == f && self.is_associative(f) {
+				subs.extend(sub.sub_terms().iter().cloned())
+			} else {
+				subs.push(sub)
+			}
+		}
+
+		Term::new(f.clone(), subs)
+	}
+
+	/// Flatten nested applications of associative symbols.
+	pub fn flatten_pattern<X: Clone>(&self, pattern: &Pattern<F, X>) -> Pattern<F, X> {
+		match pattern.kind() {
+			PatternKind::Var(_) => pattern.clone(),
+			PatternKind::Cons(f, subs) => {
+				let mut flat_subs = Vec::with_capacity(subs.len());
+				for sub in subs.iter() {
+					let sub = self.flatten_pattern(sub);
+					match sub.kind() {
+						PatternKind::Cons(g, sub_subs) if g == f && self.is_associative(f) => flat_subs.extend(sub_subs.iter().cloned()),
+						_ => flat_subs.push(sub)
+					}
+				}
+
+				Pattern::cons(f.clone(), flat_subs)
+			}
+		}
+	}
+
+	/// Equality of flattened terms modulo the axioms.
+	fn equal(&self, a: &Term<F>, b: &Term<F>) -> bool {
+		if a.symbol() != b.symbol() || a.sub_terms().len() != b.sub_terms().len() {
+			return false
+		}
+
+		if self.is_commutative(a.symbol()) {
+			let mut remaining: Vec<&Term<F>> = b.sub_terms().iter().collect();
+			for sub in a.sub_terms().iter() {
+				match remaining.iter().position(|other| self.equal(sub, other)) {
+					Some(i) => {
+						remaining.swap_remove(i);
+					},
+					None => return false
+				}
+			}
+
+			true
+		} else {
+			a.sub_terms().iter().zip(b.sub_terms().iter()).all(|(a, b)| self.equal(a, b))
+		}
+	}
+
+	/// Check if the two terms are equal modulo the axioms.
+	pub fn equivalent(&self, a: &Term<F>, b: &Term<F>) -> bool {
+		self.equal(&self.flatten_term(a), &self.flatten_term(b))
+	}
+
+	/// Every match of the pattern against the term, modulo the axioms.
+	///
+	/// The pattern and the term are flattened first, and bindings are flattened terms.
+	/// Associative symbols match at the root only: to match a pattern `f(p, q)` against
+	/// a part of the arguments of `f`, add an extension variable `f(p, q, x)`.
+	pub fn matches<X: Clone + PartialEq>(&self, pattern: &Pattern<F, X>, term: &Term<F>) -> Vec<Substitution<F, X>> {
+		let pattern = self.flatten_pattern(pattern);
+		let term = self.flatten_term(term);
+
+		let mut solutions: Vec<Bindings<F, X>> = Vec::new();
+		for sigma in self.match_term(&pattern, &term, Vec::new()) {
+			let duplicate = solutions.iter().any(|other| {
+				other.iter().all(|(x, t)| sigma.iter().any(|(y, u)| x == y && self.equal(t, u)))
+			});
+
+			if !duplicate {
+				solutions.push(sigma)
+			}
+		}
+
+		solutions.into_iter().map(|sigma| {
+			sigma.into_iter().map(|(x, t)| (x, Pattern::from_like(&t))).collect()
+		}).collect()
+	}
+
+	/// Rewrite the term at its root position modulo the axioms, using the first applicable rule.
+	///
+	/// The result is flattened.
+	pub fn rewrite<X: Clone + PartialEq>(&self, system: &RewriteSystem<F, X>, term: &Term<F>) -> Option<Term<F>> {
+		system.rules().iter().find_map(|rule| {
+			let sigma = self.matches(rule.lhs(), term).into_iter().next()?;
+			Some(self.flatten_term(&sigma.instantiate(rule.rhs())?))
+		})
+	}
+
+	fn match_term<X: Clone + PartialEq>(&self, pattern: &Pattern<F, X>, term: &Term<F>, sigma: Bindings<F, X>) -> Vec<Bindings<F, X>> {
+		match pattern.kind() {
+			PatternKind::Var(x) => {
+				match sigma.iter().find(|(y, _)| x == y) {
+					Some((_, t)) => {
+						if self.equal(t, term) {
+							vec![sigma]
+						} else {
+							Vec::new()
+						}
+					},
+					None => {
+						let mut sigma = sigma;
+						sigma.push((x.clone(), term.clone()));
+						vec![sigma]
+					}
+				}
+			},
+			PatternKind::Cons(f, subs) => {
+				if f != term.symbol() {
+					return Vec::new()
+				}
+
+				let terms: Vec<&Term<F>> = term.sub_terms().iter().collect();
+				match self.axioms(f) {
+					None => {
+						if subs.len() != terms.len() {
+							return Vec::new()
+						}
+
+						let mut solutions = vec![sigma];
+						for (sub, t) in subs.iter().zip(terms.iter()) {
+							solutions = solutions.into_iter().flat_map(|sigma| self.match_term(sub, t, sigma)).collect()
+						}
+
+						solutions
+					},
+					Some(Axioms::Commutative) => {
+						if subs.len() != terms.len() {
+							return Vec::new()
+						}
+
+						self.match_permutations(subs, terms, sigma)
+					},
+					Some(Axioms::Associative) => self.match_segments(f, subs, &terms, sigma),
+					Some(Axioms::AssociativeCommutative) => self.match_multisets(f, subs, terms, sigma)
+				}
+			}
+		}
+	}
+
+	/// Match each pattern against a distinct term, in any order.
+	fn match_permutations<X: Clone + PartialEq>(&self, patterns: &[Pattern<F, X>], terms: Vec<&Term<F>>, sigma: Bindings<F, X>) -> Vec<Bindings<F, X>> {
+		match patterns.split_first() {
+			None => vec![sigma],
+			Some((first, rest)) => {
+				let mut solutions = Vec::new();
+				for i in 0..terms.len() {
+					let mut remaining = terms.clone();
+					let t = remaining.remove(i);
+					for sigma in self.match_term(first, t, sigma.clone()) {
+						solutions.extend(self.match_permutations(rest, remaining.clone(), sigma))
+					}
+				}
+
+				solutions
+			}
+		}
+	}
+
+	/// The term bound to a variable under an associative symbol `f`, seen as a sequence of arguments of `f`.
+	fn arguments<'a>(&self, f: &F, t: &'a Term<F>) -> Vec<&'a Term<F>> {
+		if t.symbol() == f {
+			t.sub_terms().iter().collect()
+		} else {
+			vec![t]
+		}
+	}
+
+	/// The term bound to a variable matching a non-empty sequence of arguments of `f`.
+	fn segment(&self, f: &F, terms: &[&Term<F>]) -> Term<F> {
+		if terms.len() == 1 {
+			terms[0].clone()
+		} else {
+			Term::new(f.clone(), terms.iter().map(|t| (*t).clone()).collect())
+		}
+	}
+
+	/// Match the arguments of an associative symbol: each variable matches a non-empty segment of arguments.
+	fn match_segments<X: Clone + PartialEq>(&self, f: &F, patterns: &[Pattern<F, X>], terms: &[&Term<F>], sigma: Bindings<F, X>) -> Vec<Bindings<F, X>> {
+		let (first, rest) = match patterns.split_first() {
+			Some(split) => split,
+			None => return if terms.is_empty() { vec![sigma] } else { Vec::new() }
+		};
+
+		if terms.len() < patterns.len() {
+			return Vec::new()
+		}
+
+		match first.kind() {
+			PatternKind::Var(x) => {
+				if let Some((_, t)) = sigma.iter().find(|(y, _)| x == y) {
+					let args = self.arguments(f, t);
+					let n = args.len();
+					if n <= terms.len() && args.iter().zip(terms.iter()).all(|(a, b)| self.equal(a, b)) {
+						self.match_segments(f, rest, &terms[n..], sigma)
+					} else {
+						Vec::new()
+					}
+				} else {
+					let mut solutions = Vec::new();
+					for k in 1..=(terms.len() - rest.len()) {
+						let mut sigma = sigma.clone();
+						sigma.push((x.clone(), self.segment(f, &terms[..k])));
+						solutions.extend(self.match_segments(f, rest, &terms[k..], sigma))
+					}
+
+					solutions
+				}
+			},
+			PatternKind::Cons(_, _) => {
+				self.match_term(first, terms[0], sigma).into_iter().flat_map(|sigma| self.match_segments(f, rest, &terms[1..], sigma)).collect()
+			}
+		}
+	}
+
+	/// Match the arguments of an associative-commutative symbol:
+	/// each variable matches a non-empty sub-multiset of arguments.
+	fn match_multisets<X: Clone + PartialEq>(&self, f: &F, patterns: &[Pattern<F, X>], terms: Vec<&Term<F>>, sigma: Bindings<F, X>) -> Vec<Bindings<F, X>> {
+		let (variables, constructors): (Vec<_>, Vec<_>) = patterns.iter().partition(|p| p.symbol().is_none());
+		if terms.len() < patterns.len() {
+			return Vec::new()
+		}
+
+		// Match the constructor patterns first, each against one argument.
+		let mut partial = vec![(sigma, terms)];
+		for p in constructors {
+			let mut next = Vec::new();
+			for (sigma, terms) in partial {
+				for i in 0..terms.len() {
+					let mut remaining = terms.clone();
+					let t = remaining.remove(i);
+					for sigma in self.match_term(p, t, sigma.clone()) {
+						next.push((sigma, remaining.clone()))
+					}
+				}
+			}
+
+			partial = next
+		}
+
+		let variables: Vec<&X> = variables.into_iter().map(|p| match p.kind() {
+			PatternKind::Var(x) => x,
+			_ => unreachable!()
+		}).collect();
+
+		partial.into_iter().flat_map(|(sigma, terms)| self.distribute(f, &variables, terms, sigma)).collect()
+	}
+
+	/// Distribute the remaining arguments of an associative-commutative symbol among the variables.
+	fn distribute<X: Clone + PartialEq>(&self, f: &F, variables: &[&X], terms: Vec<&Term<F>>, sigma: Bindings<F, X>) -> Vec<Bindings<F, X>> {
+		let (x, rest) = match variables.split_first() {
+			Some(split) => split,
+			None => return if terms.is_empty() { vec![sigma] } else { Vec::new() }
+		};
+
+		if let Some((_, t)) = sigma.iter().find(|(y, _)| y == *x) {
+			// Remove the arguments of the bound term.
+			let mut remaining = terms;
+			for arg in self.arguments(f, t) {
+				match remaining.iter().position(|other| self.equal(arg, other)) {
+					Some(i) => {
+						remaining.remove(i);
+					},
+					None => return Vec::new()
+				}
+			}
+
+			return self.distribute(f, rest, remaining, sigma)
+		}
+
+		if rest.is_empty() {
+			if terms.is_empty() {
+				return Vec::new()
+			}
+
+			let mut sigma = sigma;
+			sigma.push(((*x).clone(), self.segment(f, &terms)));
+			return vec![sigma]
+		}
+
+		// Every non-empty sub-multiset, keeping at least one argument per remaining variable.
+		let mut solutions = Vec::new();
+		let n = terms.len();
+		let mut chosen = vec![false; n];
+		// Enumerate the subsets in binary counting order.
+		while let Some(i) = chosen.iter().position(|c| !c) {
+			for c in &mut chosen[..i] {
+				*c = false
+			}
+			chosen[i] = true;
+
+			let selected: Vec<&Term<F>> = terms.iter().zip(chosen.iter()).filter(|(_, c)| **c).map(|(t, _)| *t).collect();
+			if n - selected.len() < rest.len() {
+				continue
+			}
+
+			let remaining: Vec<&Term<F>> = terms.iter().zip(chosen.iter()).filter(|(_, c)| !**c).map(|(t, _)| *t).collect();
+			let mut sigma = sigma.clone();
+			sigma.push(((*x).clone(), self.segment(f, &selected)));
+			solutions.extend(self.distribute(f, rest, remaining, sigma))
+		}
+
+		solutions
+	}
+}
+
+impl<F: Clone + Eq + Hash> Theory<F> {
+	/// Complete set of unifiers modulo commutativity.
+	///
+	/// Commutative symbols, including associative-commutative ones,
+	/// may be applied to their arguments in any order.
+	/// Associativity is ignored: the patterns are not flattened, so the set of unifiers
+	/// may be incomplete for associative symbols.
+	/// The set of unifiers is finite, and every unifier is idempotent.
+	pub fn unify<X: Clone + PartialEq>(&self, a: &Pattern<F, X>, b: &Pattern<F, X>) -> Vec<Substitution<F, X>> {
+		let mut unifiers: Vec<Substitution<F, X>> = Vec::new();
+		let mut problems = vec![(vec![(a.clone(), b.clone())], Substitution::new())];
+		while let Some((mut equations, sigma)) = problems.pop() {
+			let (s, t) = match equations.pop() {
+				Some(equation) => equation,
+				None => {
+					if !unifiers.contains(&sigma) {
+						unifiers.push(sigma)
+					}
+					continue
+				}
+			};
+
+			match (s.kind(), t.kind()) {
+				(PatternKind::Var(x), PatternKind::Var(y)) if x == y => problems.push((equations, sigma)),
+				(PatternKind::Var(x), _) => {
+					if let Some(problem) = eliminate(x, &t, equations, &sigma) {
+						problems.push(problem)
+					}
+				},
+				(_, PatternKind::Var(y)) => {
+					if let Some(problem) = eliminate(y, &s, equations, &sigma) {
+						problems.push(problem)
+					}
+				},
+				(PatternKind::Cons(f, subs_s), PatternKind::Cons(g, subs_t)) => {
+					if f != g || subs_s.len() != subs_t.len() {
+						continue
+					}
+
+					if self.is_commutative(f) {
+						for permutation in permutations(subs_t.len()) {
+							let mut equations = equations.clone();
+							equations.extend(subs_s.iter().cloned().zip(permutation.into_iter().map(|i| subs_t[i].clone())));
+							problems.push((equations, sigma.clone()))
+						}
+					} else {
+						equations.extend(subs_s.iter().cloned().zip(subs_t.iter().cloned()));
+						problems.push((equations, sigma))
+					}
+				}
+			}
+		}
+
+		unifiers
+	}
+}
+
+impl<F: Clone + Ord + Hash> Theory<F> {
+	/// Canonical form of the term modulo the axioms.
+	///
+	/// Nested applications of associative symbols are flattened,
+	/// and the arguments of commutative symbols are sorted using the term ordering.
+	/// Two terms are equal modulo the axioms if and only if their canonical forms are equal,
+	/// so canonical forms can be compared with `==` and stored in a `HashSet`.
+	pub fn canonical(&self, term: &Term<F>) -> Term<F> {
+		let f = term.symbol();
+		let mut subs = Vec::with_capacity(term.sub_terms().len());
+		for sub in term.sub_terms().iter() {
+			let sub = self.canonical(sub);
+			if sub.symbol() == f && self.is_associative(f) {
+				subs.extend(sub.sub_terms().iter().cloned())
+			} else {
+				subs.push(sub)
+			}
+		}
+
+		if self.is_commutative(f) {
+			subs.sort()
+		}
+
+		Term::new(f.clone(), subs)
+	}
+}
+
+/// Bind the variable in the remaining equations and in the substitution, unless it occurs in the pattern.
+fn eliminate<F: Clone + PartialEq, X: Clone + PartialEq>(x: &X, p: &Pattern<F, X>, equations: Vec<Equation<F, X>>, sigma: &Substitution<F, X>) -> Option<Problem<F, X>> {
+	if p.variable_occurrences().any(|y| x == y) {
+		return None
+	}
+
+	let theta: Substitution<F, X> = std::iter::once((x.clone(), p.clone())).collect();
+	let equations = equations.into_iter().map(|(s, t)| (theta.apply(&s), theta.apply(&t))).collect();
+	Some((equations, sigma.compose(&theta)))
+}
+
+/// Every permutation of `0..n`.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+	if n == 0 {
+		return vec![Vec::new()]
+	}
+
+	let mut result = Vec::new();
+	for permutation in permutations(n - 1) {
+		for i in 0..n {
+			let mut p = permutation.clone();
+			p.insert(i, n - 1);
+			result.push(p)
+		}
+	}
+
+	result
+}
+
+impl<F: Clone + Eq + Hash> Default for Theory<F> {
+	fn default() -> Theory<F> {
+		Theory::new()
+	}
+}
+
+impl<F: Clone + Eq + Hash> std::iter::FromIterator<(F, Axioms)> for Theory<F> {
+	fn from_iter<I: IntoIterator<Item = (F, Axioms)>>(iter: I) -> Theory<F> {
+		let mut theory = Theory::new();
+		for (f, axioms) in iter {
+			theory.declare(f, axioms);
+		}
+
+		theory
+	}
+}
@@ -0,0 +1,275 @@
+use std::collections::VecDeque;
+use crate::{Term, Pattern, PatternKind, Position};
+
+/// Tree-like structures that can be traversed.
+pub trait Tree: Sized {
+	fn children(&self) -> &[Self];
+}
+
+impl<F> Tree for Term<F> {
+	fn children(&self) -> &[Self] {
+		self.sub_terms()
+	}
+}
+
+impl<F, X> Tree for Pattern<F, X> {
+	fn children(&self) -> &[Self] {
+		match self.kind() {
+			PatternKind::Cons(_, subs) => subs,
+			PatternKind::Var(_) => &[]
+		}
+	}
+}
+
+/// Information tracked along each node of a traversal.
+///
+/// `()` tracks nothing, `usize` tracks the depth of each node, and [`Position`] tracks its position.
+pub trait Tracker<'a, T: 'a>: Sized {
+	type Item;
+
+	fn root() -> Self;
+
+	fn child(&self, i: usize) -> Self;
+
+	fn item(self, node: &'a T) -> Self::Item;
+}
+
+impl<'a, T: 'a> Tracker<'a, T> for () {
+	type Item = &'a T;
+
+	fn root() {}
+
+	fn child(&self, _i: usize) {}
+
+	fn item(self, node: &'a T) -> &'a T {
+		node
+	}
+}
+
+impl<'a, T: 'a> Tracker<'a, T> for usize {
+	type Item = (usize, &'a T);
+
+	fn root() -> usize {
+		0
+	}
+
+	fn child(&self, _i: usize) -> usize {
+		self + 1
+	}
+
+	fn item(self, node: &'a T) -> (usize, &'a T) {
+		(self, node)
+	}
+}
+
+impl<'a, T: 'a> Tracker<'a, T> for Position {
+	type Item = (Position, &'a T);
+
+	fn root() -> Position {
+		Position::root()
+	}
+
+	fn child(&self, i: usize) -> Position {
+		Position::child(self, i)
+	}
+
+	fn item(self, node: &'a T) -> (Position, &'a T) {
+		(self, node)
+	}
+}
+
+/// Pre-order (depth-first, parents first) traversal.
+pub struct PreOrder<'a, T, K = ()> {
+	root: &'a T,
+	stack: Vec<(&'a T, K)>
+}
+
+impl<'a, T: Tree, K: Tracker<'a, T>> PreOrder<'a, T, K> {
+	pub fn new(root: &'a T) -> Self {
+		PreOrder {
+			root,
+			stack: vec![(root, K::root())]
+		}
+	}
+
+	/// Restart the traversal from the root, yielding the depth of each node.
+	///
+	/// This does not continue from the current state of the iterator:
+	/// nodes that were already yielded are visited again.
+	pub fn with_depths(self) -> PreOrder<'a, T, usize> {
+		PreOrder::new(self.root)
+	}
+
+	/// Restart the traversal from the root, yielding the position of each node.
+	///
+	/// This does not continue from the current state of the iterator:
+	/// nodes that were already yielded are visited again.
+	pub fn with_positions(self) -> PreOrder<'a, T, Position> {
+		PreOrder::new(self.root)
+	}
+}
+
+impl<'a, T: Tree, K: Tracker<'a, T>> Iterator for PreOrder<'a, T, K> {
+	type Item = K::Item;
+
+	fn next(&mut self) -> Option<K::Item> {
+		let (node, k) = self.stack.pop()?;
+		for (i, child) in node.children().iter().enumerate().rev() {
+			self.stack.push((child, k.child(i)))
+		}
+
+		Some(k.item(node))
+	}
+}
+
+/// Post-order (depth-first, children first) traversal.
+pub struct PostOrder<'a, T, K = ()> {
+	root: &'a T,
+	stack: Vec<(&'a T, K, usize)>
+}
+
+impl<'a, T: Tree, K: Tracker<'a, T>> PostOrder<'a, T, K> {
+	pub fn new(root: &'a T) -> Self {
+		PostOrder {
+			root,
+			stack: vec![(root, K::root(), 0)]
+		}
+	}
+
+	/// Restart the traversal from the root, yielding the depth of each node.
+	///
+	/// This does not continue from the current state of the iterator:
+	/// nodes that were already yielded are visited again.
+	pub fn with_depths(self) -> PostOrder<'a, T, usize> {
+		PostOrder::new(self.root)
+	}
+
+	/// Restart the traversal from the root, yielding the position of each node.
+	///
+	/// This does not continue from the current state of the iterator:
+	/// nodes that were already yielded are visited again.
+	pub fn with_positions(self) -> PostOrder<'a, T, Position> {
+		PostOrder::new(self.root)
+	}
+}
+
+impl<'a, T: Tree, K: Tracker<'a, T>> Iterator for PostOrder<'a, T, K> {
+	type Item = K::Item;
+
+	fn next(&mut self) -> Option<K::Item> {
+		loop {
+			let (node, k, next) = self.stack.last_mut()?;
+			match node.children().get(*next) {
+				Some(child) => {
+					let child_k = k.child(*next);
+					*next += 1;
+					self.stack.push((child, child_k, 0))
+				},
+				None => {
+					let (node, k, _) = self.stack.pop().unwrap();
+					return Some(k.item(node))
+				}
+			}
+		}
+	}
+}
+
+/// Level-order (breadth-first) traversal.
+pub struct LevelOrder<'a, T, K = ()> {
+	root: &'a T,
+	queue: VecDeque<(&'a T, K)>
+}
+
+impl<'a, T: Tree, K: Tracker<'a, T>> LevelOrder<'a, T, K> {
+	pub fn new(root: &'a T) -> Self {
+		let mut queue = VecDeque::new();
+		queue.push_back((root, K::root()));
+		LevelOrder {
+			root,
+			queue
+		}
+	}
+
+	/// Restart the traversal from the root, yielding the depth of each node.
+	///
+	/// This does not continue from the current state of the iterator:
+	/// nodes that were already yielded are visited again.
+	pub fn with_depths(self) -> LevelOrder<'a, T, usize> {
+		LevelOrder::new(self.root)
+	}
+
+	/// Restart the traversal from the root, yielding the position of each node.
+	///
+	/// This does not continue from the current state of the iterator:
+	/// nodes that were already yielded are visited again.
+	pub fn with_positions(self) -> LevelOrder<'a, T, Position> {
+		LevelOrder::new(self.root)
+	}
+}
+
+impl<'a, T: Tree, K: Tracker<'a, T>> Iterator for LevelOrder<'a, T, K> {
+	type Item = K::Item;
+
+	fn next(&mut self) -> Option<K::Item> {
+		let (node, k) = self.queue.pop_front()?;
+		for (i, child) in node.children().iter().enumerate() {
+			self.queue.push_back((child, k.child(i)))
+		}
+
+		Some(k.item(node))
+	}
+}
+
+impl<F> Term<F> {
+	/// Iterate over every sub-term (including the term itself) in pre-order.
+	pub fn pre_order(&self) -> PreOrder<'_, Term<F>> {
+		PreOrder::new(self)
+	}
+
+	/// Iterate over every sub-term (including the term itself) in post-order.
+	pub fn post_order(&self) -> PostOrder<'_, Term<F>> {
+		PostOrder::new(self)
+	}
+
+	/// Iterate over every sub-term (including the term itself) in level-order.
+	pub fn level_order(&self) -> LevelOrder<'_, Term<F>> {
+		LevelOrder::new(self)
+	}
+
+	/// Iterate over every symbol occurrence of the term, in pre-order.
+	pub fn symbols(&self) -> impl Iterator<Item = &F> {
+		self.pre_order().map(Term::symbol)
+	}
+}
+
+impl<F, X> Pattern<F, X> {
+	/// Iterate over every sub-pattern (including the pattern itself) in pre-order.
+	pub fn pre_order(&self) -> PreOrder<'_, Pattern<F, X>> {
+		PreOrder::new(self)
+	}
+
+	/// Iterate over every sub-pattern (including the pattern itself) in post-order.
+	pub fn post_order(&self) -> PostOrder<'_, Pattern<F, X>> {
+		PostOrder::new(self)
+	}
+
+	/// Iterate over every sub-pattern (including the pattern itself) in level-order.
+	pub fn level_order(&self) -> LevelOrder<'_, Pattern<F, X>> {
+		LevelOrder::new(self)
+	}
+
+	/// Iterate over every symbol occurrence of the pattern, in pre-order.
+	pub fn symbols(&self) -> impl Iterator<Item = &F> {
+		self.pre_order().filter_map(Pattern::symbol)
+	}
+
+	/// Iterate over every variable occurrence of the pattern, in pre-order.
+	///
+	/// Unlike [`variables`](Pattern::variables), variables occurring multiple times are repeated.
+	pub fn variable_occurrences(&self) -> impl Iterator<Item = &X> {
+		self.pre_order().filter_map(|p| match p.kind() {
+			PatternKind::Var(x) => Some(x),
+			_ => None
+		})
+	}
+}
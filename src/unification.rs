@@ -0,0 +1,127 @@
+use std::fmt;
+use crate::{Pattern, PatternKind, Substitution};
+
+/// Unification failure.
+pub enum UnificationError<F, X> {
+	/// The two symbols (or their arities) differ.
+	Clash(F, F),
+
+	/// The variable occurs in the pattern it must be bound to.
+	Occurs(X, Pattern<F, X>),
+
+	/// The variable cannot be bound, and differs from the pattern it is unified with.
+	///
+	/// Only happens when some variables are treated as constants.
+	Rigid(X, Pattern<F, X>)
+}
+
+impl<F: fmt::Debug, X: fmt::Debug> fmt::Debug for UnificationError<F, X> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			UnificationError::Clash(a, b) => write!(f, "Clash({:?}, {:?})", a, b),
+			UnificationError::Occurs(x, p) => write!(f, "Occurs({:?}, {:?})", x, p),
+			UnificationError::Rigid(x, p) => write!(f, "Rigid({:?}, {:?})", x, p)
+		}
+	}
+}
+
+impl<F: fmt::Display, X: fmt::Display> fmt::Display for UnificationError<F, X> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			UnificationError::Clash(a, b) => write!(f, "symbol clash between {} and {}", a, b),
+			UnificationError::Occurs(x, p) => write!(f, "variable {} occurs in {}", x, p),
+			UnificationError::Rigid(x, p) => write!(f, "rigid variable {} cannot be unified with {}", x, p)
+		}
+	}
+}
+
+impl<F: fmt::Debug + fmt::Display, X: fmt::Debug + fmt::Display> std::error::Error for UnificationError<F, X> {}
+
+impl<F: Clone + PartialEq, X: Clone + PartialEq> Pattern<F, X> {
+	/// Compute the most general unifier of the two patterns.
+	///
+	/// The returned substitution is idempotent.
+	pub fn unify(&self, other: &Pattern<F, X>) -> Result<Substitution<F, X>, UnificationError<F, X>> {
+		let mut sigma = Substitution::new();
+		self.unify_with(other, &mut sigma)?;
+		Ok(sigma.normalized())
+	}
+
+	/// Extend the given triangular substitution into a unifier of the two patterns.
+	///
+	/// The input substitution must not be cyclic.
+	/// Bindings are added without being applied to the previous ones, so the result may not be idempotent.
+	/// When the unification fails, the substitution may have been partially extended.
+	pub fn unify_with(&self, other: &Pattern<F, X>, sigma: &mut Substitution<F, X>) -> Result<(), UnificationError<F, X>> {
+		unify_flexible(self, other, sigma, &|_| true)
+	}
+
+	/// Check if the two patterns are unifiable.
+	pub fn is_unifiable(&self, other: &Pattern<F, X>) -> bool {
+		self.unify_with(other, &mut Substitution::new()).is_ok()
+	}
+}
+
+/// Follow the variable bindings of a triangular substitution until a constructor or free variable is reached.
+fn walk<F: Clone, X: Clone + PartialEq>(p: &Pattern<F, X>, sigma: &Substitution<F, X>) -> Pattern<F, X> {
+	let mut p = p;
+	while let PatternKind::Var(x) = p.kind() {
+		match sigma.get(x) {
+			Some(q) => p = q,
+			None => break
+		}
+	}
+
+	p.clone()
+}
+
+fn occurs<F, X: PartialEq>(x: &X, p: &Pattern<F, X>, sigma: &Substitution<F, X>) -> bool {
+	match p.kind() {
+		PatternKind::Var(y) => {
+			x == y || match sigma.get(y) {
+				Some(q) => occurs(x, q, sigma),
+				None => false
+			}
+		},
+		PatternKind::Cons(_, subs) => subs.iter().any(|sub| occurs(x, sub, sigma))
+	}
+}
+
+/// Extend the given triangular substitution into a unifier of the two patterns,
+/// where only the variables satisfying `flexible` may be bound.
+///
+/// Other variables are treated as constants.
+pub(crate) fn unify_flexible<F: Clone + PartialEq, X: Clone + PartialEq, P: Fn(&X) -> bool>(a: &Pattern<F, X>, b: &Pattern<F, X>, sigma: &mut Substitution<F, X>, flexible: &P) -> Result<(), UnificationError<F, X>> {
+	let mut stack = vec![(a.clone(), b.clone())];
+	while let Some((a, b)) = stack.pop() {
+		let a = walk(&a, sigma);
+		let b = walk(&b, sigma);
+		match (a.kind(), b.kind()) {
+			(PatternKind::Var(x), PatternKind::Var(y)) if x == y => (),
+			(PatternKind::Var(x), _) if flexible(x) => bind(x, &b, sigma)?,
+			(_, PatternKind::Var(y)) if flexible(y) => bind(y, &a, sigma)?,
+			(PatternKind::Var(x), _) => return Err(UnificationError::Rigid(x.clone(), b.clone())),
+			(_, PatternKind::Var(y)) => return Err(UnificationError::Rigid(y.clone(), a.clone())),
+			(PatternKind::Cons(f, subs_a), PatternKind::Cons(g, subs_b)) => {
+				if f != g || subs_a.len() != subs_b.len() {
+					return Err(UnificationError::Clash(f.clone(), g.clone()))
+				}
+
+				for (sub_a, sub_b) in subs_a.iter().zip(subs_b.iter()).rev() {
+					stack.push((sub_a.clone(), sub_b.clone()))
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn bind<F: Clone, X: Clone + PartialEq>(x: &X, p: &Pattern<F, X>, sigma: &mut Substitution<F, X>) -> Result<(), UnificationError<F, X>> {
+	if occurs(x, p, sigma) {
+		Err(UnificationError::Occurs(x.clone(), sigma.resolve(p)))
+	} else {
+		sigma.bind(x.clone(), p.clone());
+		Ok(())
+	}
+}
@@ -82,7 +82,7 @@ impl<'a, T: Incr> Var<'a, T> {
 		}
 		Var {
 			count: namespace,
-			id: id
+			id
 		}
 	}
 }
@@ -99,7 +99,7 @@ impl<'a, T: Incr + Hash + Eq + Clone> Spawnable for Var<'a, T> {
 		namespace.set(id.incr());
 		Var {
 			count: namespace,
-			id: id
+			id
 		}
 	}
 }
@@ -118,7 +118,7 @@ impl<'a, T: Incr + Ord + Eq> Ord for Var<'a, T> {
 
 impl<'a, T: Incr + Eq> PartialEq for Var<'a, T> {
 	fn eq(&self, other: &Var<'a, T>) -> bool {
-		self.count as *const Cell<T> == other.count as *const Cell<T> && self.id == other.id
+		std::ptr::eq(self.count, other.count) && self.id == other.id
 	}
 }
 
@@ -0,0 +1,154 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+use terms::{Term, Pattern, PatternKind, Position, Substitution, TermInterner};
+use common::*;
+
+fn term_size(t: &Term<Symbol>) -> usize {
+	1 + t.sub_terms().iter().map(term_size).sum::<usize>()
+}
+
+fn term_depth(t: &Term<Symbol>) -> u64 {
+	t.sub_terms().iter().map(|sub| term_depth(sub) + 1).max().unwrap_or(0)
+}
+
+fn pattern_size(p: &Pattern<Symbol, u32>) -> usize {
+	match p.kind() {
+		PatternKind::Cons(_, subs) => 1 + subs.iter().map(pattern_size).sum::<usize>(),
+		PatternKind::Var(_) => 1
+	}
+}
+
+fn pattern_depth(p: &Pattern<Symbol, u32>) -> u64 {
+	match p.kind() {
+		PatternKind::Cons(_, subs) => subs.iter().map(|sub| pattern_depth(sub) + 1).max().unwrap_or(0),
+		PatternKind::Var(_) => 0
+	}
+}
+
+fn variable_count(p: &Pattern<Symbol, u32>) -> usize {
+	match p.kind() {
+		PatternKind::Cons(_, subs) => subs.iter().map(variable_count).sum(),
+		PatternKind::Var(_) => 1
+	}
+}
+
+/// Structural equality, without the cached values.
+fn same_term(a: &Term<Symbol>, b: &Term<Symbol>) -> bool {
+	a.symbol() == b.symbol() && a.sub_terms().len() == b.sub_terms().len() && a.sub_terms().iter().zip(b.sub_terms().iter()).all(|(a, b)| same_term(a, b))
+}
+
+fn same_pattern(a: &Pattern<Symbol, u32>, b: &Pattern<Symbol, u32>) -> bool {
+	match (a.kind(), b.kind()) {
+		(PatternKind::Cons(f, subs_a), PatternKind::Cons(g, subs_b)) => {
+			f == g && subs_a.len() == subs_b.len() && subs_a.iter().zip(subs_b.iter()).all(|(a, b)| same_pattern(a, b))
+		},
+		(PatternKind::Var(x), PatternKind::Var(y)) => x == y,
+		_ => false
+	}
+}
+
+fn hash<T: Hash>(value: &T) -> u64 {
+	let mut hasher = DefaultHasher::new();
+	value.hash(&mut hasher);
+	hasher.finish()
+}
+
+fn check_term(t: &Term<Symbol>) {
+	assert_eq!(t.size(), term_size(t));
+	assert_eq!(t.depth(), term_depth(t));
+}
+
+fn check_pattern(p: &Pattern<Symbol, u32>) {
+	assert_eq!(p.size(), pattern_size(p));
+	assert_eq!(p.depth(), pattern_depth(p));
+	assert_eq!(p.variable_count(), variable_count(p));
+	assert_eq!(p.is_ground(), variable_count(p) == 0);
+}
+
+#[test]
+fn cached_term_values() {
+	let mut interner = TermInterner::new();
+	for t in random_terms(120, 200, 20) {
+		t.pre_order().for_each(check_term);
+		check_term(&interner.intern(&t));
+		check_term(&Term::new(*t.symbol(), t.sub_terms().to_vec()));
+		for p in t.positions() {
+			let u = t.replace_at(&p, term("h(a, g(b), c)")).unwrap();
+			u.pre_order().for_each(check_term);
+		}
+	}
+}
+
+#[test]
+fn cached_pattern_values() {
+	let sigma: Substitution<Symbol, u32> = vec![(0, pattern("f(?1, g(?2))")), (1, pattern("a"))].into_iter().collect();
+	for p in random_patterns(121, 200, 20, 3) {
+		p.pre_order().for_each(check_pattern);
+		check_pattern(&sigma.apply(&p));
+		check_pattern(&p.map_variables(&|x| if *x == 2 { pattern("h(?0, ?0, b)") } else { Pattern::var(*x) }));
+		check_pattern(&Pattern::from_like(&ground_instance(&p)));
+		check_pattern(&p.replace_at(&Position::root(), pattern("?0")).unwrap());
+	}
+}
+
+/// The instance of the pattern where every variable is replaced by `a`.
+fn ground_instance(p: &Pattern<Symbol, u32>) -> Term<Symbol> {
+	match p.kind() {
+		PatternKind::Cons(f, subs) => Term::new(*f, subs.iter().map(ground_instance).collect()),
+		PatternKind::Var(_) => term("a")
+	}
+}
+
+#[test]
+fn term_equality() {
+	let terms = random_terms(122, 150, 12);
+
+	// Copies built independently, so that the sub-terms are not shared.
+	let copies: Vec<Term<Symbol>> = terms.iter().map(|t| term(&t.to_string())).collect();
+	for (i, a) in terms.iter().enumerate() {
+		// Compute the hashes of some of the terms only.
+		if i % 2 == 0 {
+			hash(a);
+		}
+
+		for (j, b) in copies.iter().enumerate() {
+			if j % 3 == 0 {
+				hash(b);
+			}
+
+			assert_eq!(a == b, same_term(a, b));
+			assert_eq!(a == b, i == j);
+			if a == b {
+				assert_eq!(hash(a), hash(b));
+			}
+		}
+	}
+}
+
+#[test]
+fn pattern_equality() {
+	let patterns = random_patterns(123, 150, 12, 3);
+	let copies: Vec<Pattern<Symbol, u32>> = patterns.iter().map(|p| p.map_variables(&|x| Pattern::var(*x))).collect();
+	for (i, a) in patterns.iter().enumerate() {
+		if i % 2 == 0 {
+			hash(a);
+		}
+
+		for (j, b) in copies.iter().enumerate() {
+			if j % 3 == 0 {
+				hash(b);
+			}
+
+			assert_eq!(a == b, same_pattern(a, b));
+			assert_eq!(a == b, i == j);
+			if a == b {
+				assert_eq!(hash(a), hash(b));
+			}
+		}
+	}
+}
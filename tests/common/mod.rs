@@ -0,0 +1,104 @@
+#![allow(dead_code)]
+
+use std::fmt;
+use std::str::FromStr;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use terms::{Term, Pattern, Ranked, Generator};
+
+/// Symbols of the test alphabet: constants `a`, `b`, `c` and `e`,
+/// unary `g` and `i`, binary `f` and `m`, and ternary `h`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Symbol(pub char);
+
+impl Ranked for Symbol {
+	fn arity(&self) -> usize {
+		match self.0 {
+			'g' | 'i' => 1,
+			'f' | 'm' => 2,
+			'h' => 3,
+			_ => 0
+		}
+	}
+}
+
+impl FromStr for Symbol {
+	type Err = ();
+
+	fn from_str(s: &str) -> Result<Symbol, ()> {
+		let mut chars = s.chars();
+		match (chars.next(), chars.next()) {
+			(Some(c), None) if "abcgifmhe".contains(c) => Ok(Symbol(c)),
+			_ => Err(())
+		}
+	}
+}
+
+impl fmt::Display for Symbol {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+pub fn term(s: &str) -> Term<Symbol> {
+	s.parse().unwrap()
+}
+
+/// Parse a pattern whose variables are indexes, such as `f(?0, g(a))`.
+pub fn pattern(s: &str) -> Pattern<Symbol, u32> {
+	s.parse().unwrap()
+}
+
+fn generator() -> Generator<Symbol> {
+	let symbols = vec![
+		(Symbol('a'), 3.0),
+		(Symbol('b'), 2.0),
+		(Symbol('c'), 1.0),
+		(Symbol('g'), 1.0),
+		(Symbol('f'), 2.0),
+		(Symbol('h'), 0.5)
+	];
+
+	Generator::with_weights(symbols).max_depth(4)
+}
+
+/// Distinct random terms of size at most `max_size`.
+pub fn random_terms(seed: u64, count: usize, max_size: usize) -> Vec<Term<Symbol>> {
+	let generator = generator().max_size(max_size);
+	let mut rng = StdRng::seed_from_u64(seed);
+	let mut terms = Vec::new();
+	for _ in 0..(count * 10) {
+		let t = generator.term(&mut rng).unwrap();
+		if !terms.contains(&t) {
+			terms.push(t);
+			if terms.len() == count {
+				break
+			}
+		}
+	}
+
+	terms
+}
+
+/// Distinct random patterns of size at most `max_size`, with variables among `?0` to `?(variables - 1)`.
+pub fn random_patterns(seed: u64, count: usize, max_size: usize, variables: usize) -> Vec<Pattern<Symbol, u32>> {
+	let generator = generator().max_size(max_size).variable_probability(0.3);
+	let mut rng = StdRng::seed_from_u64(seed);
+	let mut patterns = Vec::new();
+	for _ in 0..(count * 10) {
+		let p = generator.indexed_pattern(&mut rng, variables).unwrap();
+		if !patterns.contains(&p) {
+			patterns.push(p);
+			if patterns.len() == count {
+				break
+			}
+		}
+	}
+
+	patterns
+}
+
+/// Rename the variables of the pattern by adding the given offset.
+pub fn shift(pattern: &Pattern<Symbol, u32>, offset: u32) -> Pattern<Symbol, u32> {
+	pattern.map_variables(&|x| Pattern::var(x + offset))
+}
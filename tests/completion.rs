@@ -0,0 +1,124 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use std::cmp::Ordering;
+use terms::{Pattern, Rule, RewriteSystem, CompletionError, Joinability, ReductionOrder};
+use terms::simplification::{Kbo, Lpo, Comparison};
+use common::*;
+
+/// Precedence `i > m > e`, then alphabetical.
+fn precedence(f: &Symbol, g: &Symbol) -> Option<Ordering> {
+	let rank = |s: &Symbol| match s.0 {
+		'i' => 2,
+		'm' => 1,
+		_ => 0
+	};
+
+	Some(rank(f).cmp(&rank(g)).then(f.cmp(g)))
+}
+
+fn weight(f: &Symbol) -> usize {
+	match f.0 {
+		'i' | 'm' => 0,
+		_ => 1
+	}
+}
+
+fn group() -> Vec<(Pattern<Symbol, u32>, Pattern<Symbol, u32>)> {
+	vec![
+		(pattern("m(m(?0, ?1), ?2)"), pattern("m(?0, m(?1, ?2))")),
+		(pattern("m(e, ?0)"), pattern("?0")),
+		(pattern("m(i(?0), ?0)"), pattern("e"))
+	]
+}
+
+/// Check if the system contains a variant of the given rule.
+fn contains(system: &RewriteSystem<Symbol, u32>, lhs: &str, rhs: &str) -> bool {
+	let expected = Pattern::cons(Symbol('f'), vec![pattern(lhs), pattern(rhs)]);
+	system.rules().iter().any(|rule| {
+		let found = Pattern::cons(Symbol('f'), vec![rule.lhs().clone(), rule.rhs().clone()]);
+		found.matches_like(&expected).is_some() && expected.matches_like(&found).is_some()
+	})
+}
+
+#[test]
+fn group_axioms() {
+	let kbo = Kbo::new(precedence, weight, 1);
+	let system = RewriteSystem::complete(&group(), &kbo, 100).unwrap();
+
+	assert_eq!(system.len(), 10);
+	for (lhs, rhs) in &[
+		("m(e, ?0)", "?0"),
+		("m(?0, e)", "?0"),
+		("m(i(?0), ?0)", "e"),
+		("m(?0, i(?0))", "e"),
+		("m(m(?0, ?1), ?2)", "m(?0, m(?1, ?2))"),
+		("m(i(?0), m(?0, ?1))", "?1"),
+		("m(?0, m(i(?0), ?1))", "?1"),
+		("i(e)", "e"),
+		("i(i(?0))", "?0"),
+		("i(m(?0, ?1))", "m(i(?1), i(?0))")
+	] {
+		assert!(contains(&system, lhs, rhs), "missing rule {} -> {}", lhs, rhs);
+	}
+
+	for rule in system.rules() {
+		assert_eq!(kbo.compare(rule.lhs(), rule.rhs()), Comparison::Greater);
+	}
+
+	assert_eq!(system.local_confluence(20), Joinability::Joinable);
+
+	// Equal group elements have the same normal form.
+	let (a, normal) = system.normalize_pattern(&pattern("i(m(m(?0, i(?1)), i(?0)))"), 100);
+	assert!(normal);
+	assert_eq!(a, pattern("m(?0, m(?1, i(?0)))"));
+	assert_eq!(system.normalize_pattern(&pattern("m(i(m(?1, i(?0))), ?1)"), 100).0, pattern("?0"));
+}
+
+#[test]
+fn failures() {
+	let lpo = Lpo::new(precedence);
+	let commutativity = vec![(pattern("m(?0, ?1)"), pattern("m(?1, ?0)"))];
+	match RewriteSystem::complete(&commutativity, &lpo, 100) {
+		Err(CompletionError::Unorientable(_, _)) => (),
+		_ => panic!("commutativity should not be orientable")
+	}
+
+	let kbo = Kbo::new(precedence, weight, 1);
+	match RewriteSystem::complete(&group(), &kbo, 5) {
+		Err(CompletionError::Divergence(rules)) => assert!(rules.len() <= 5),
+		_ => panic!("the completion should exceed the rule limit")
+	}
+}
+
+#[test]
+fn already_convergent() {
+	let lpo = Lpo::new(precedence);
+	let equations = vec![
+		(pattern("m(e, ?0)"), pattern("?0")),
+		(pattern("i(e)"), pattern("e"))
+	];
+
+	let system = RewriteSystem::complete(&equations, &lpo, 10).unwrap();
+	assert_eq!(system.len(), 2);
+	assert!(system.critical_pairs().is_empty());
+}
+
+#[test]
+fn critical_pairs_are_renamed_apart() {
+	let rule = |lhs: &str, rhs: &str| Rule::new(pattern(lhs), pattern(rhs)).ok().unwrap();
+	let system: RewriteSystem<Symbol, u32> = vec![rule("g(g(?0))", "f(?0, ?0)")].into_iter().collect();
+
+	let pairs = system.critical_pairs();
+	assert_eq!(pairs.len(), 1);
+	let pair = &pairs[0];
+	assert_eq!(pair.position.len(), 1);
+	assert_eq!(pair.peak, pattern("g(g(g(?1)))"));
+	assert_eq!(pair.left, pattern("g(f(?1, ?1))"));
+	assert_eq!(pair.right, pattern("f(g(?1), g(?1))"));
+	assert_eq!(system.joinability(&pair.left, &pair.right, 10), Joinability::NotJoinable);
+	assert_eq!(system.local_confluence(10), Joinability::NotJoinable);
+	assert!(!system.is_orthogonal());
+}
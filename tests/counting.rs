@@ -0,0 +1,51 @@
+extern crate terms;
+extern crate rand;
+
+use std::collections::HashMap;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use terms::{Ranked, UniformGenerator, RandomError, Counter};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+struct Symbol(char, usize);
+
+impl Ranked for Symbol {
+	fn arity(&self) -> usize {
+		self.1
+	}
+}
+
+const A: Symbol = Symbol('a', 0);
+const F: Symbol = Symbol('f', 2);
+
+#[test]
+fn counting() {
+	// Binary trees are counted by the Catalan numbers.
+	let mut counter = Counter::new(vec![A, F]);
+	let catalan = [1, 0, 1, 0, 2, 0, 5, 0, 14, 0, 42];
+	for (i, n) in catalan.iter().enumerate() {
+		assert_eq!(counter.count(i + 1), Some(*n));
+	}
+}
+
+#[test]
+fn uniform() {
+	// There are 5 terms of size 7, each should be generated about 2000 times.
+	let mut generator = UniformGenerator::new(&[A, F]);
+	let mut rng = StdRng::seed_from_u64(5);
+	let mut samples = HashMap::new();
+	for _ in 0..10000 {
+		let t = generator.term(&mut rng, 7).unwrap();
+		assert_eq!(t.size(), 7);
+		*samples.entry(format!("{:?}", t)).or_insert(0) += 1;
+	}
+
+	assert_eq!(samples.len(), 5);
+	assert!(samples.values().all(|n| *n > 1700 && *n < 2300));
+}
+
+#[test]
+fn no_term_of_size() {
+	let mut rng = StdRng::seed_from_u64(3);
+	assert_eq!(UniformGenerator::new(&[A, F]).term(&mut rng, 2).unwrap_err(), RandomError::NoTerm);
+}
@@ -0,0 +1,70 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use terms::{Pattern, Substitution, DecisionTree};
+use common::*;
+
+/// Every arm matching the term, in order, by trying each pattern in sequence.
+fn sequential(patterns: &[Pattern<Symbol, u32>], t: &terms::Term<Symbol>) -> Vec<(usize, Substitution<Symbol, u32>)> {
+	patterns.iter().enumerate().filter_map(|(i, p)| p.matches(t).map(|sigma| (i, sigma))).collect()
+}
+
+#[test]
+fn first_match() {
+	let patterns = vec![
+		pattern("f(a, ?0)"),
+		pattern("f(?0, ?0)"),
+		pattern("f(g(?0), ?1)"),
+		pattern("?0")
+	];
+	let tree = DecisionTree::new(&patterns);
+	assert_eq!(tree.len(), 4);
+
+	assert_eq!(tree.first_match(&term("f(a, b)")).map(|(i, _)| i), Some(0));
+	assert_eq!(tree.first_match(&term("f(b, b)")).map(|(i, _)| i), Some(1));
+	assert_eq!(tree.first_match(&term("f(g(c), b)")).map(|(i, _)| i), Some(2));
+	assert_eq!(tree.first_match(&term("f(b, c)")).map(|(i, _)| i), Some(3));
+
+	let (_, sigma) = tree.first_match(&term("f(g(c), b)")).unwrap();
+	assert_eq!(sigma, pattern("f(g(?0), ?1)").matches(&term("f(g(c), b)")).unwrap());
+
+	let matches: Vec<usize> = tree.all_matches(&term("f(a, a)")).into_iter().map(|(i, _)| i).collect();
+	assert_eq!(matches, vec![0, 1, 3]);
+}
+
+#[test]
+fn empty() {
+	let tree: DecisionTree<Symbol, u32> = DecisionTree::new(&[]);
+	assert!(tree.is_empty());
+	assert!(tree.first_match(&term("a")).is_none());
+}
+
+#[test]
+fn same_symbol_different_arities() {
+	let patterns = vec![pattern("f(a, ?0)"), pattern("f(?0)"), pattern("f")];
+	let tree = DecisionTree::new(&patterns);
+	for t in ["f(a, b)", "f(b)", "f", "f(b, a)"].iter().map(|s| term(s)) {
+		assert_eq!(tree.all_matches(&t), sequential(&patterns, &t));
+	}
+}
+
+#[test]
+fn against_sequential_matching() {
+	for seed in 0..5 {
+		// Non-linear patterns are included.
+		let patterns = random_patterns(20 + seed, 20, 6, 3);
+		let tree = DecisionTree::new(&patterns);
+
+		let mut found = 0;
+		for t in random_terms(30 + seed, 300, 7) {
+			let expected = sequential(&patterns, &t);
+			assert_eq!(tree.all_matches(&t), expected);
+			assert_eq!(tree.first_match(&t), expected.into_iter().next());
+			found += tree.all_matches(&t).len();
+		}
+
+		assert!(found > 0);
+	}
+}
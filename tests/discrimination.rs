@@ -0,0 +1,97 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use terms::DiscriminationTree;
+use terms::discrimination::{keys, Key};
+use common::*;
+
+#[test]
+fn keys_in_pre_order() {
+	let keys: Vec<_> = keys(&pattern("f(?0, g(a))")).collect();
+	assert_eq!(keys, vec![
+		Key::Symbol(Symbol('f'), 2),
+		Key::Variable,
+		Key::Symbol(Symbol('g'), 1),
+		Key::Symbol(Symbol('a'), 0)
+	]);
+}
+
+#[test]
+fn insert_get_remove() {
+	let mut tree = DiscriminationTree::new();
+	assert!(tree.is_empty());
+	assert_eq!(tree.insert(pattern("f(?0, a)"), 1), None);
+	assert_eq!(tree.insert(pattern("f(?1, a)"), 2), None);
+	assert_eq!(tree.insert(pattern("f(?0, a)"), 3), Some(1));
+	assert_eq!(tree.len(), 2);
+
+	assert_eq!(tree.get(&pattern("f(?0, a)")), Some(&3));
+	assert_eq!(tree.get(&pattern("f(?2, a)")), None);
+	assert_eq!(tree.remove(&pattern("f(?0, a)")), Some(3));
+	assert_eq!(tree.remove(&pattern("f(?0, a)")), None);
+	assert_eq!(tree.len(), 1);
+	assert_eq!(tree.iter().count(), 1);
+}
+
+#[test]
+fn generalizations() {
+	let patterns = random_patterns(0, 300, 7, 3);
+	let tree: DiscriminationTree<_, _, _> = patterns.iter().cloned().enumerate().map(|(i, p)| (p, i)).collect();
+	assert_eq!(tree.len(), patterns.len());
+
+	let mut found = 0;
+	for t in random_terms(1, 300, 7) {
+		let mut retrieved: Vec<usize> = tree.generalizations(&t).into_iter().map(|r| {
+			assert_eq!(r.substitution.instantiate(r.pattern).as_ref(), Some(&t));
+			*r.value
+		}).collect();
+		retrieved.sort();
+
+		let expected: Vec<usize> = (0..patterns.len()).filter(|i| patterns[*i].matches(&t).is_some()).collect();
+		assert_eq!(retrieved, expected);
+		found += retrieved.len();
+	}
+
+	assert!(found > 300);
+}
+
+#[test]
+fn instances() {
+	let patterns = random_patterns(2, 300, 7, 3);
+	let tree: DiscriminationTree<_, _, _> = patterns.iter().cloned().enumerate().map(|(i, p)| (p, i)).collect();
+
+	let mut found = 0;
+	for q in random_patterns(3, 300, 5, 2) {
+		let mut retrieved: Vec<usize> = tree.instances(&q).into_iter().map(|r| {
+			assert_eq!(&r.substitution.apply(&q), r.pattern);
+			*r.value
+		}).collect();
+		retrieved.sort();
+
+		let expected: Vec<usize> = (0..patterns.len()).filter(|i| q.matches_like(&patterns[*i]).is_some()).collect();
+		assert_eq!(retrieved, expected);
+		found += retrieved.len();
+	}
+
+	assert!(found > 300);
+}
+
+#[test]
+fn retrieval_after_removal() {
+	let patterns = random_patterns(4, 200, 7, 2);
+	let mut tree: DiscriminationTree<_, _, _> = patterns.iter().cloned().enumerate().map(|(i, p)| (p, i)).collect();
+	for (i, p) in patterns.iter().enumerate().filter(|(i, _)| i % 2 == 0) {
+		assert_eq!(tree.remove(p), Some(i));
+	}
+	assert_eq!(tree.len(), patterns.len() / 2);
+
+	for t in random_terms(5, 200, 7) {
+		let mut retrieved: Vec<usize> = tree.generalizations(&t).into_iter().map(|r| *r.value).collect();
+		retrieved.sort();
+
+		let expected: Vec<usize> = (0..patterns.len()).filter(|i| i % 2 == 1 && patterns[*i].matches(&t).is_some()).collect();
+		assert_eq!(retrieved, expected);
+	}
+}
@@ -0,0 +1,85 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use terms::{Term, Counter, Enumerator, DepthEnumerator};
+use common::*;
+
+fn alphabet(symbols: &str) -> Vec<Symbol> {
+	symbols.chars().map(Symbol).collect()
+}
+
+/// Check that the terms are distinct.
+fn distinct(terms: &[Term<Symbol>]) -> bool {
+	let mut sorted = terms.to_vec();
+	sorted.sort();
+	sorted.dedup();
+	sorted.len() == terms.len()
+}
+
+#[test]
+fn by_size() {
+	let alphabet = alphabet("abgf");
+	let terms: Vec<_> = Enumerator::new(&alphabet).take(2000).collect();
+	assert!(distinct(&terms));
+	assert!(terms.windows(2).all(|w| w[0].size() <= w[1].size()));
+
+	// Every term of each complete size is enumerated.
+	let mut counter = Counter::new(alphabet);
+	let last = terms.last().unwrap().size();
+	for size in 1..last {
+		let n = terms.iter().filter(|t| t.size() == size).count();
+		assert_eq!(counter.count(size), Some(n as u128));
+	}
+}
+
+#[test]
+fn by_depth() {
+	let terms: Vec<_> = DepthEnumerator::new(&alphabet("abf")).take(38).collect();
+	assert!(distinct(&terms));
+	assert!(terms.windows(2).all(|w| w[0].depth() <= w[1].depth()));
+
+	// There are `2 + n * n` terms of depth at most `d + 1`, where `n` is the number of terms of depth at most `d`.
+	let counts: Vec<usize> = (0..3).map(|d| terms.iter().filter(|t| t.depth() == d).count()).collect();
+	assert_eq!(counts, vec![2, 4, 32]);
+
+	let unary: Vec<_> = DepthEnumerator::new(&alphabet("ag")).take(5).collect();
+	assert_eq!(unary, vec![term("a"), term("g(a)"), term("g(g(a))"), term("g(g(g(a)))"), term("g(g(g(g(a))))")]);
+}
+
+#[test]
+fn rank_unrank() {
+	let alphabet = alphabet("abgh");
+	let mut counter = Counter::new(alphabet.clone());
+	for (i, t) in Enumerator::new(&alphabet).take(1000).enumerate() {
+		assert_eq!(counter.unrank(i as u128).as_ref(), Some(&t));
+		assert_eq!(counter.rank(&t), Some(i as u128));
+	}
+
+	assert_eq!(counter.rank(&term("f(a, b)")), None);
+	let terms: Vec<_> = random_terms(70, 200, 10).into_iter().filter(|t| t.pre_order().all(|s| alphabet.contains(s.symbol()))).collect();
+	assert!(!terms.is_empty());
+	for t in &terms {
+		let i = counter.rank(t).unwrap();
+		assert_eq!(counter.unrank(i).as_ref(), Some(t));
+	}
+}
+
+#[test]
+fn finite_alphabets() {
+	let constants = alphabet("abc");
+	let terms: Vec<_> = Enumerator::new(&constants).collect();
+	assert_eq!(terms, vec![term("a"), term("b"), term("c")]);
+	assert_eq!(DepthEnumerator::new(&constants).count(), 3);
+
+	let mut counter = Counter::new(constants);
+	assert_eq!(counter.unrank(2), Some(term("c")));
+	assert_eq!(counter.unrank(3), None);
+
+	// Without constants, there is no term at all.
+	let unary = alphabet("gf");
+	assert_eq!(Enumerator::new(&unary).count(), 0);
+	assert_eq!(DepthEnumerator::new(&unary).count(), 0);
+	assert_eq!(Counter::new(unary).unrank(0), None);
+}
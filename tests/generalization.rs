@@ -0,0 +1,84 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use std::cell::Cell;
+use terms::{Term, Pattern, Var, Generalization};
+use common::*;
+
+type V<'a> = Var<'a, u32>;
+
+#[test]
+fn repeated_disagreements() {
+	let namespace = Cell::new(0);
+	let a = term("h(a, a, g(b))");
+	let b = term("h(b, b, g(c))");
+	let (p, sigma_a, sigma_b): (Pattern<Symbol, V>, _, _) = Pattern::generalize(&a, &b, &&namespace);
+
+	// The disagreement pair `(a, b)` occurs twice, and is replaced by the same variable.
+	assert_eq!(p.to_string(), "h(x0, x0, g(x1))");
+	assert_eq!(sigma_a.len(), 2);
+	assert_eq!(sigma_a.instantiate(&p), Some(a));
+	assert_eq!(sigma_b.instantiate(&p), Some(b));
+}
+
+#[test]
+fn same_terms() {
+	let namespace = Cell::new(0);
+	let a = term("f(a, g(b))");
+	let (p, sigma_a, sigma_b): (Pattern<Symbol, V>, _, _) = Pattern::generalize(&a, &a, &&namespace);
+	assert_eq!(p.to_string(), "f(a, g(b))");
+	assert!(sigma_a.is_empty());
+	assert!(sigma_b.is_empty());
+}
+
+#[test]
+fn patterns() {
+	let namespace = Cell::new(0);
+	let x = Var::from(0, &&namespace);
+	let a: Pattern<Symbol, V> = Pattern::cons(Symbol('f'), vec![Pattern::var(x.clone()), Pattern::cons(Symbol('a'), vec![])]);
+	let b: Pattern<Symbol, V> = Pattern::cons(Symbol('f'), vec![Pattern::var(x.clone()), Pattern::var(x)]);
+
+	// Shared variables are kept, and fresh variables do not clash with them.
+	let (p, sigma_a, sigma_b) = Pattern::generalize(&a, &b, &&namespace);
+	assert_eq!(p.to_string(), "f(x0, x1)");
+	assert_eq!(sigma_a.apply(&p), a);
+	assert_eq!(sigma_b.apply(&p), b);
+}
+
+#[test]
+fn generalize_all() {
+	let namespace = Cell::new(0);
+	let terms = [term("h(a, b, a)"), term("h(b, a, b)"), term("h(c, c, c)")];
+	let items: Vec<&Term<Symbol>> = terms.iter().collect();
+	let Generalization { pattern, substitutions } = Pattern::<Symbol, V>::generalize_all(&items, &&namespace).unwrap();
+	assert_eq!(pattern.to_string(), "h(x0, x1, x0)");
+	assert_eq!(substitutions.len(), 3);
+	for (sigma, t) in substitutions.iter().zip(terms.iter()) {
+		assert_eq!(sigma.instantiate(&pattern).as_ref(), Some(t));
+	}
+
+	let items = [&terms[0]];
+	let Generalization { pattern, substitutions } = Pattern::<Symbol, V>::generalize_all(&items, &&namespace).unwrap();
+	assert_eq!(pattern.to_string(), "h(a, b, a)");
+	assert!(substitutions[0].is_empty());
+
+	assert!(Pattern::<Symbol, V>::generalize_all::<Term<Symbol>>(&[], &&namespace).is_none());
+}
+
+#[test]
+fn random_inputs() {
+	let terms = random_terms(50, 40, 9);
+	for group in terms.chunks(4) {
+		let namespace = Cell::new(0);
+		let items: Vec<&Term<Symbol>> = group.iter().collect();
+		let Generalization { pattern, substitutions } = Pattern::<Symbol, V>::generalize_all(&items, &&namespace).unwrap();
+
+		// Each substitution gives back its input exactly.
+		for (sigma, t) in substitutions.iter().zip(group.iter()) {
+			assert_eq!(sigma.instantiate(&pattern).as_ref(), Some(t));
+			assert!(pattern.size() <= t.size());
+		}
+	}
+}
@@ -0,0 +1,66 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use terms::TermInterner;
+use common::*;
+
+#[test]
+fn sharing() {
+	let mut interner = TermInterner::new();
+	let a = interner.intern(&term("f(g(a), h(a, b, g(a)))"));
+	let b = interner.intern(&term("f(g(a), h(a, b, g(a)))"));
+	assert!(a.ptr_eq(&b));
+	assert_eq!(a, b);
+
+	// Equal sub-terms are shared too.
+	let g = interner.intern(&term("g(a)"));
+	assert!(a.sub_terms()[0].ptr_eq(&g));
+	assert!(a.sub_terms()[1].sub_terms()[2].ptr_eq(&g));
+
+	let made = interner.make(Symbol('f'), vec![g.clone(), b.sub_terms()[1].clone()]);
+	assert!(made.ptr_eq(&a));
+}
+
+#[test]
+fn distinct_terms() {
+	let mut interner = TermInterner::new();
+	let terms = random_terms(60, 200, 8);
+	let interned: Vec<_> = terms.iter().map(|t| interner.intern(t)).collect();
+	for (i, a) in interned.iter().enumerate() {
+		assert_eq!(a, &terms[i]);
+		for (j, b) in interned.iter().enumerate() {
+			assert_eq!(a.ptr_eq(b), i == j);
+		}
+	}
+
+	// Same sub-terms under different symbols.
+	let a = interner.intern(&term("g(a)"));
+	let b = interner.intern(&term("i(a)"));
+	assert!(!a.ptr_eq(&b));
+	assert_ne!(a, b);
+}
+
+#[test]
+fn collect_garbage() {
+	let mut interner = TermInterner::new();
+	assert!(interner.is_empty());
+
+	let t = interner.intern(&term("f(g(a), b)"));
+	let g = t.sub_terms()[0].clone();
+	assert_eq!(interner.len(), 2);
+
+	// Dead entries are kept until the next collection.
+	drop(t);
+	assert_eq!(interner.len(), 2);
+	interner.collect_garbage();
+	assert_eq!(interner.len(), 1);
+	assert!(!interner.is_empty());
+
+	assert!(interner.intern(&term("g(a)")).ptr_eq(&g));
+	drop(g);
+	interner.collect_garbage();
+	assert_eq!(interner.len(), 0);
+	assert!(interner.is_empty());
+}
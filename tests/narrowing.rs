@@ -0,0 +1,82 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use terms::{Substitution, Rule, RewriteSystem, NarrowingStrategy};
+use common::*;
+
+/// Peano addition, where `e` is zero, `g` is the successor and `m` is the addition.
+fn peano() -> RewriteSystem<Symbol, u32> {
+	vec![
+		Rule::new(pattern("m(e, ?0)"), pattern("?0")).ok().unwrap(),
+		Rule::new(pattern("m(g(?0), ?1)"), pattern("g(m(?0, ?1))")).ok().unwrap()
+	].into_iter().collect()
+}
+
+fn solution(bindings: &[(u32, &str)]) -> Substitution<Symbol, u32> {
+	bindings.iter().map(|(x, p)| (*x, pattern(p))).collect()
+}
+
+#[test]
+fn narrowing_steps() {
+	let system = peano();
+	let steps = system.narrowing_steps(&pattern("m(?0, e)"));
+	assert_eq!(steps.len(), 2);
+
+	assert_eq!(steps[0].rule, 0);
+	assert!(steps[0].position.is_root());
+	assert_eq!(steps[0].result, pattern("e"));
+	assert_eq!(steps[0].substitution.apply(&pattern("?0")), pattern("e"));
+
+	// The rule is renamed apart from the pattern.
+	assert_eq!(steps[1].rule, 1);
+	assert_eq!(steps[1].result, pattern("g(m(?1, e))"));
+	assert_eq!(steps[1].substitution.apply(&pattern("?0")), pattern("g(?1)"));
+}
+
+#[test]
+fn solve() {
+	let system = peano();
+	let lhs = pattern("m(?0, ?1)");
+	let rhs = pattern("g(g(e))");
+
+	let expected = vec![
+		solution(&[(0, "e"), (1, "g(g(e))")]),
+		solution(&[(0, "g(e)"), (1, "g(e)")]),
+		solution(&[(0, "g(g(e))"), (1, "e")])
+	];
+
+	for strategy in [NarrowingStrategy::Basic, NarrowingStrategy::Innermost] {
+		let solutions: Vec<_> = system.solve(&lhs, &rhs, strategy, 5).collect();
+		assert_eq!(solutions, expected);
+
+		for sigma in &solutions {
+			let (a, _) = system.normalize_pattern(&sigma.apply(&lhs), 100);
+			assert_eq!(a, rhs);
+		}
+	}
+}
+
+#[test]
+fn solve_with_variables_left() {
+	let system = peano();
+
+	// `x + 1 = 1 + x` holds for every `x`, but narrowing enumerates its instances.
+	let solutions: Vec<_> = system.solve(&pattern("m(?0, g(e))"), &pattern("g(?0)"), NarrowingStrategy::Basic, 6).take(3).collect();
+	assert_eq!(solutions, vec![
+		solution(&[(0, "e")]),
+		solution(&[(0, "g(e)")]),
+		solution(&[(0, "g(g(e))")])
+	]);
+}
+
+#[test]
+fn no_solution() {
+	let system = peano();
+	assert_eq!(system.solve(&pattern("m(?0, g(e))"), &pattern("e"), NarrowingStrategy::Basic, 6).count(), 0);
+
+	// Syntactically unifiable equations are solved without narrowing.
+	let solutions: Vec<Substitution<Symbol, u32>> = system.solve(&pattern("?0"), &pattern("g(e)"), NarrowingStrategy::Basic, 0).collect();
+	assert_eq!(solutions, vec![solution(&[(0, "g(e)")])]);
+}
@@ -0,0 +1,174 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use std::cmp::Ordering;
+use terms::{Term, Pattern, TermOrder, Ordered, Comparison, ReductionOrder};
+use terms::order::{Lexicographic, SizeLexicographic, DepthLexicographic, VariablesLast};
+use terms::simplification::{Lpo, Rpo, Kbo, Status, OrdPrecedence};
+use common::{Symbol, pattern, random_patterns};
+
+fn t(s: &str) -> Term<String> {
+	s.parse().unwrap()
+}
+
+#[test]
+fn lexicographic() {
+	assert_eq!(Lexicographic::<VariablesLast>::compare(&t("f(a)"), &t("f(a, b)")), Ordering::Less);
+	assert_eq!(Lexicographic::<VariablesLast>::compare(&t("g(f(a), c)"), &t("g(f(a, b), b)")), Ordering::Less);
+	assert_eq!(Lexicographic::<VariablesLast>::compare(&t("f(b)"), &t("f(a, b)")), Ordering::Greater);
+	assert_eq!(Lexicographic::<VariablesLast>::compare(&t("g(a)"), &t("f(b)")), Ordering::Greater);
+}
+
+#[test]
+fn size_and_depth_first() {
+	assert_eq!(SizeLexicographic::<VariablesLast>::compare(&t("z"), &t("a(a)")), Ordering::Less);
+	assert_eq!(SizeLexicographic::<VariablesLast>::compare(&t("f(a, b)"), &t("f(g(a))")), Ordering::Less);
+	assert_eq!(DepthLexicographic::<VariablesLast>::compare(&t("f(a, b, c)"), &t("f(g(a))")), Ordering::Less);
+}
+
+#[test]
+fn variables() {
+	let p = |s: &str| -> Pattern<String, String> { s.parse().unwrap() };
+	assert_eq!(Lexicographic::<VariablesLast>::compare(&p("f(?x)"), &p("f(a)")), Ordering::Greater);
+	assert_eq!(<Lexicographic>::compare(&p("f(?x)"), &p("f(a)")), Ordering::Less);
+}
+
+#[test]
+fn sort() {
+	let mut terms: Vec<Term<String>> = vec!["f(a, b)", "b", "g(a)", "a"].into_iter().map(t).collect();
+	<SizeLexicographic as TermOrder<Term<String>>>::sort(&mut terms);
+	let sorted: Vec<String> = terms.iter().map(|t| t.to_string()).collect();
+	assert_eq!(sorted, vec!["a", "b", "g(a)", "f(a, b)"]);
+
+	let mut ordered: Vec<Ordered<Term<String>, DepthLexicographic>> = vec!["g(g(a))", "f(a, b)", "a"].into_iter().map(|s| Ordered::new(t(s))).collect();
+	ordered.sort();
+	let sorted: Vec<String> = ordered.iter().map(|t| t.to_string()).collect();
+	assert_eq!(sorted, vec!["a", "f(a, b)", "g(g(a))"]);
+}
+
+/// Precedence `i > m > f`, then alphabetical.
+fn precedence(f: &Symbol, g: &Symbol) -> Option<Ordering> {
+	let rank = |s: &Symbol| match s.0 {
+		'i' => 3,
+		'm' => 2,
+		'f' => 1,
+		_ => 0
+	};
+
+	Some(rank(f).cmp(&rank(g)).then(f.cmp(g)))
+}
+
+fn weight(_f: &Symbol) -> usize {
+	1
+}
+
+fn compare<O: ReductionOrder<Symbol, u32>>(order: &O, a: &str, b: &str) -> Comparison {
+	order.compare(&pattern(a), &pattern(b))
+}
+
+/// Check the properties shared by every simplification order on random patterns.
+fn simplification_order<O: ReductionOrder<Symbol, u32>>(order: &O) {
+	let patterns = random_patterns(80, 100, 8, 3);
+	for p in &patterns {
+		assert_eq!(order.compare(p, p), Comparison::Equal);
+
+		// Subterm property.
+		for sub in p.pre_order().skip(1) {
+			assert_eq!(order.compare(p, sub), Comparison::Greater, "{:?} > {:?}", p, sub);
+			assert_eq!(order.compare(sub, p), Comparison::Less);
+		}
+
+		for q in &patterns {
+			let c = order.compare(p, q);
+			assert_eq!(order.compare(q, p), c.reverse());
+			if p != q && p.embeds(q) {
+				assert_eq!(c, Comparison::Greater)
+			}
+
+			// Every variable of the smaller pattern occurs in the greater one.
+			if c == Comparison::Greater {
+				assert!(q.variables().all(|x| p.variables().any(|y| x == y)))
+			}
+		}
+	}
+}
+
+#[test]
+fn lpo() {
+	let lpo = Lpo::new(precedence);
+	assert_eq!(compare(&lpo, "m(m(?0, ?1), ?2)", "m(?0, m(?1, ?2))"), Comparison::Greater);
+	assert_eq!(compare(&lpo, "i(m(?0, ?1))", "m(i(?1), i(?0))"), Comparison::Greater);
+	assert_eq!(compare(&lpo, "m(?0, f(?1, ?2))", "f(m(?0, ?1), m(?0, ?2))"), Comparison::Greater);
+	assert_eq!(compare(&lpo, "g(a)", "i(a)"), Comparison::Less);
+
+	// Incomparable pairs.
+	assert_eq!(compare(&lpo, "f(?0, ?1)", "f(?1, ?0)"), Comparison::Incomparable);
+	assert_eq!(compare(&lpo, "g(?0)", "?1"), Comparison::Incomparable);
+	assert_eq!(compare(&lpo, "i(?0)", "g(?1)"), Comparison::Incomparable);
+
+	// Incomparable symbols.
+	let partial = Lpo::new(|f: &Symbol, g: &Symbol| if f == g { Some(Ordering::Equal) } else { None });
+	assert_eq!(compare(&partial, "g(a)", "i(a)"), Comparison::Incomparable);
+	assert_eq!(compare(&partial, "g(i(a))", "i(a)"), Comparison::Greater);
+
+	simplification_order(&lpo);
+	simplification_order(&Lpo::new(OrdPrecedence));
+}
+
+#[test]
+fn rpo() {
+	let mpo = Rpo::multiset(precedence);
+
+	// Arguments of multiset status symbols are compared regardless of their order.
+	assert_eq!(compare(&mpo, "m(?0, ?1)", "m(?1, ?0)"), Comparison::Equal);
+	assert_eq!(compare(&mpo, "m(g(?0), ?1)", "m(?1, ?0)"), Comparison::Greater);
+	assert_eq!(compare(&mpo, "m(m(?0, ?1), ?2)", "m(?0, m(?1, ?2))"), Comparison::Incomparable);
+	assert_eq!(compare(&mpo, "m(?0, f(?1, ?2))", "f(m(?0, ?1), m(?0, ?2))"), Comparison::Greater);
+	assert_eq!(compare(&mpo, "h(a, b, c)", "h(c, a, b)"), Comparison::Equal);
+	assert_eq!(compare(&mpo, "h(a, c, c)", "h(c, b, b)"), Comparison::Greater);
+
+	// Lexicographic status for `m` only.
+	let rpo = Rpo::new(precedence, |f: &Symbol| if f.0 == 'm' { Status::Lexicographic } else { Status::Multiset });
+	assert_eq!(compare(&rpo, "m(m(?0, ?1), ?2)", "m(?0, m(?1, ?2))"), Comparison::Greater);
+	assert_eq!(compare(&rpo, "m(?0, ?1)", "m(?1, ?0)"), Comparison::Incomparable);
+	assert_eq!(compare(&rpo, "f(?0, ?1)", "f(?1, ?0)"), Comparison::Equal);
+
+	simplification_order(&mpo);
+	simplification_order(&rpo);
+}
+
+#[test]
+fn kbo() {
+	let kbo = Kbo::new(precedence, weight, 1);
+	assert_eq!(kbo.weight_of(&pattern("f(?0, g(a))")), 4);
+	assert_eq!(compare(&kbo, "m(m(?0, ?1), ?2)", "m(?0, m(?1, ?2))"), Comparison::Greater);
+	assert_eq!(compare(&kbo, "f(?0, ?0)", "g(?0)"), Comparison::Greater);
+
+	// Equal weights are compared by precedence.
+	assert_eq!(compare(&kbo, "i(a)", "g(a)"), Comparison::Greater);
+	assert_eq!(compare(&kbo, "f(a, b)", "m(b, a)"), Comparison::Less);
+
+	// The variables of the smaller pattern must occur at least as often in the greater one.
+	assert_eq!(compare(&kbo, "g(g(g(?0)))", "f(?0, ?0)"), Comparison::Incomparable);
+	assert_eq!(compare(&kbo, "f(?0, ?0)", "f(?1, a)"), Comparison::Incomparable);
+	assert_eq!(compare(&kbo, "g(?0)", "?0"), Comparison::Greater);
+
+	simplification_order(&kbo);
+}
+
+#[test]
+fn embedding() {
+	assert!(pattern("f(g(a), h(b, c, ?0))").embeds(&pattern("f(a, ?0)")));
+	assert!(pattern("g(f(a, g(b)))").embeds(&pattern("g(g(b))")));
+	assert!(pattern("?0").embeds(&pattern("?0")));
+	assert!(!pattern("?0").embeds(&pattern("?1")));
+	assert!(!pattern("a").embeds(&pattern("?0")));
+	assert!(!pattern("f(a, b)").embeds(&pattern("f(b, a)")));
+	assert!(!pattern("g(a)").embeds(&pattern("g(g(a))")));
+
+	for p in random_patterns(81, 100, 8, 3) {
+		assert!(p.pre_order().all(|sub| p.embeds(sub)));
+	}
+}
@@ -0,0 +1,55 @@
+extern crate terms;
+
+use terms::{Term, Pattern, ParseError};
+use terms::parse::{ParseErrorKind, Uppercase};
+
+#[test]
+fn term_round_trip() {
+	let t: Term<String> = "f(a, g(b, c), h)".parse().unwrap();
+	assert_eq!(t.to_string(), "f(a, g(b, c), h)");
+	assert_eq!(t.to_string().parse::<Term<String>>().unwrap(), t);
+}
+
+#[test]
+fn pattern_round_trip() {
+	let p: Pattern<String, String> = "f(?x, g(a, ?y), ?x)".parse().unwrap();
+	assert_eq!(p.variables().count(), 2);
+	assert_eq!(p.display_with_marks().to_string(), "f(?x, g(a, ?y), ?x)");
+	assert_eq!(p.display_with_marks().to_string().parse::<Pattern<String, String>>().unwrap(), p);
+
+	let p: Pattern<String, u32> = Pattern::cons("f".to_string(), vec![Pattern::var(0), Pattern::cons("a".to_string(), vec![])]);
+	assert_eq!(p.display_with_marks().to_string().parse::<Pattern<String, u32>>().unwrap(), p);
+}
+
+#[test]
+fn display_without_marks() {
+	// `Display` prints variables as they are.
+	let p: Pattern<String, String> = "f(?x, g(a, ?y))".parse().unwrap();
+	assert_eq!(p.to_string(), "f(x, g(a, y))");
+}
+
+#[test]
+fn whitespaces() {
+	let p: Pattern<String, String> = " f ( ?x ,g( a ) ) ".parse().unwrap();
+	assert_eq!(p.display_with_marks().to_string(), "f(?x, g(a))");
+}
+
+#[test]
+fn uppercase_variables() {
+	let syntax = Uppercase {
+		symbol: |id: &str| Some(id.to_string()),
+		variable: |id: &str| Some(id.to_string())
+	};
+
+	let p: Pattern<String, String> = Pattern::parse_with("f(X, a, ?y)", &syntax).unwrap();
+	assert_eq!(p.display_with_marks().to_string(), "f(?X, a, ?y)");
+}
+
+#[test]
+fn errors() {
+	let error = |s: &str| s.parse::<Pattern<String, String>>().unwrap_err();
+	assert_eq!(error("f(a"), ParseError { offset: 3, kind: ParseErrorKind::UnexpectedEnd });
+	assert_eq!(error("f(a b)"), ParseError { offset: 4, kind: ParseErrorKind::Unexpected('b') });
+	assert_eq!(error("f(?x(a))"), ParseError { offset: 2, kind: ParseErrorKind::AppliedVariable("x".to_string()) });
+	assert_eq!("f(1)".parse::<Term<u32>>().unwrap_err(), ParseError { offset: 0, kind: ParseErrorKind::InvalidSymbol("f".to_string()) });
+}
@@ -0,0 +1,90 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use terms::Position;
+use common::*;
+
+fn pos(s: &str) -> Position {
+	s.parse().unwrap()
+}
+
+#[test]
+fn operations() {
+	let p = pos("0.2");
+	assert_eq!(p, Position::new(vec![0, 2]));
+	assert_eq!(p.to_string(), "0.2");
+	assert_eq!(Position::root().to_string(), "ε");
+	assert_eq!(pos("ε"), Position::root());
+	assert!("0.a".parse::<Position>().is_err());
+
+	assert_eq!(p.child(1), pos("0.2.1"));
+	assert_eq!(p.parent(), Some(pos("0")));
+	assert_eq!(Position::root().parent(), None);
+	assert_eq!(p.concat(&pos("1.1")), pos("0.2.1.1"));
+	assert_eq!(pos("0.2.1").strip_prefix(&p), Some(pos("1")));
+	assert_eq!(pos("1.2").strip_prefix(&p), None);
+
+	assert!(p.is_prefix_of(&p));
+	assert!(!p.is_strict_prefix_of(&p));
+	assert!(Position::root().is_strict_prefix_of(&p));
+	assert!(p.is_parallel_to(&pos("0.1.0")));
+	assert!(!p.is_parallel_to(&pos("0")));
+
+	// A position comes before its extensions.
+	let mut positions = vec![pos("1"), pos("0.1"), pos(""), pos("0")];
+	positions.sort();
+	assert_eq!(positions, vec![pos(""), pos("0"), pos("0.1"), pos("1")]);
+}
+
+#[test]
+fn sub_terms() {
+	let t = term("f(g(a), h(b, c, g(a)))");
+	assert_eq!(t.at(&Position::root()), Some(&t));
+	assert_eq!(t.at(&pos("1.2")), Some(&term("g(a)")));
+	assert_eq!(t.at(&pos("1.2.0")), Some(&term("a")));
+	assert_eq!(t.at(&pos("1.3")), None);
+	assert_eq!(t.at(&pos("0.0.0")), None);
+
+	let p = pattern("f(?0, h(b, ?1, g(?0)))");
+	assert_eq!(p.at(&pos("1.1")), Some(&pattern("?1")));
+	assert_eq!(p.at(&pos("0.0")), None);
+}
+
+#[test]
+fn replace() {
+	let t = term("f(g(a), g(b))");
+	let u = t.replace_at(&pos("0.0"), term("h(a, b, c)")).unwrap();
+	assert_eq!(u, term("f(g(h(a, b, c)), g(b))"));
+	assert_eq!(t, term("f(g(a), g(b))"));
+	assert_eq!(t.replace_at(&Position::root(), term("a")), Some(term("a")));
+	assert_eq!(t.replace_at(&pos("2"), term("a")), None);
+
+	// Sub-terms outside of the path are shared.
+	assert!(u.sub_terms()[1].ptr_eq(&t.sub_terms()[1]));
+
+	let p = pattern("f(?0, g(?1))");
+	assert_eq!(p.replace_at(&pos("1.0"), pattern("g(?0)")), Some(pattern("f(?0, g(g(?0)))")));
+	assert_eq!(p.replace_at(&pos("0.0"), pattern("a")), None);
+}
+
+#[test]
+fn positions() {
+	let t = term("f(g(a), h(b, c, a))");
+	let positions = t.positions();
+	assert_eq!(positions, vec![pos(""), pos("0"), pos("0.0"), pos("1"), pos("1.0"), pos("1.1"), pos("1.2")]);
+
+	// Every position addresses a sub-term, and replacing it by itself gives back the term.
+	for t in random_terms(90, 100, 10) {
+		let positions = t.positions();
+		assert_eq!(positions.len(), t.size());
+		assert!(positions.windows(2).all(|w| w[0] < w[1]));
+		for p in &positions {
+			let sub = t.at(p).unwrap();
+			assert_eq!(t.replace_at(p, sub.clone()).as_ref(), Some(&t));
+		}
+	}
+
+	assert_eq!(pattern("f(?0, ?1)").positions(), vec![pos(""), pos("0"), pos("1")]);
+}
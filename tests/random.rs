@@ -0,0 +1,70 @@
+extern crate terms;
+extern crate rand;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use terms::{Ranked, Generator, RandomError};
+use terms::random::{DEFAULT_MAX_DEPTH, DEFAULT_MAX_SIZE};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+struct Symbol(char, usize);
+
+impl Ranked for Symbol {
+	fn arity(&self) -> usize {
+		self.1
+	}
+}
+
+const A: Symbol = Symbol('a', 0);
+const B: Symbol = Symbol('b', 0);
+const F: Symbol = Symbol('f', 2);
+const H: Symbol = Symbol('h', 3);
+
+#[test]
+fn default_bounds() {
+	// The expected number of sub-terms is greater than 1.
+	let generator = Generator::new(&[A, F, H]);
+	let mut rng = StdRng::seed_from_u64(0);
+	for _ in 0..100 {
+		let t = generator.term(&mut rng).unwrap();
+		assert!(t.size() <= DEFAULT_MAX_SIZE);
+		assert!(t.depth() <= DEFAULT_MAX_DEPTH);
+	}
+}
+
+#[test]
+fn bounds() {
+	let generator = Generator::new(&[A, B, F, H]).max_depth(3).max_size(10);
+	let mut rng = StdRng::seed_from_u64(1);
+	for _ in 0..100 {
+		let t = generator.term(&mut rng).unwrap();
+		assert!(t.size() <= 10);
+		assert!(t.depth() <= 3);
+	}
+}
+
+#[test]
+fn deterministic() {
+	let generator = Generator::with_weights(vec![(A, 1.0), (B, 2.0), (F, 1.0)]);
+	let a: Vec<_> = (0..10).scan(StdRng::seed_from_u64(2), |rng, _| generator.term(rng).ok()).collect();
+	let b: Vec<_> = (0..10).scan(StdRng::seed_from_u64(2), |rng, _| generator.term(rng).ok()).collect();
+	assert_eq!(a, b);
+}
+
+#[test]
+fn errors() {
+	let mut rng = StdRng::seed_from_u64(3);
+	assert_eq!(Generator::new(&[F]).term(&mut rng).unwrap_err(), RandomError::NoConstant);
+	assert_eq!(Generator::new(&[A]).max_size(0).term(&mut rng).unwrap_err(), RandomError::ZeroSize);
+}
+
+#[test]
+fn patterns() {
+	let generator = Generator::new(&[A, F]).max_size(20).variable_probability(0.5);
+	let mut rng = StdRng::seed_from_u64(4);
+	for _ in 0..100 {
+		let p = generator.indexed_pattern::<u32, _>(&mut rng, 3).unwrap();
+		assert!(p.size() <= 20);
+		assert!(p.variables().all(|x| *x < 3));
+	}
+}
@@ -0,0 +1,66 @@
+extern crate terms;
+
+use terms::{Term, Pattern, Rule, RewriteSystem, Strategy, Position};
+
+fn t(s: &str) -> Term<String> {
+	s.parse().unwrap()
+}
+
+fn rule(lhs: &str, rhs: &str) -> Rule<String, String> {
+	let lhs: Pattern<String, String> = lhs.parse().unwrap();
+	let rhs: Pattern<String, String> = rhs.parse().unwrap();
+	Rule::new(lhs, rhs).ok().unwrap()
+}
+
+/// `first(x, y) -> x`, with a looping constant.
+fn lazy() -> RewriteSystem<String, String> {
+	vec![
+		rule("first(?x, ?y)", "?x"),
+		rule("loop", "loop"),
+		rule("double(?x)", "pair(?x, ?x)"),
+		rule("a", "b")
+	].into_iter().collect()
+}
+
+#[test]
+fn invalid_rules() {
+	let x: Pattern<String, String> = "?x".parse().unwrap();
+	let f: Pattern<String, String> = "f(?y)".parse().unwrap();
+	assert!(Rule::new(x.clone(), f.clone()).is_err());
+	assert!(Rule::new(f, x).is_err());
+}
+
+#[test]
+fn redexes() {
+	let system = lazy();
+	let term = t("first(double(a), loop)");
+	assert!(system.is_redex(&term));
+	assert_eq!(system.redexes(&term), vec![
+		Position::root(),
+		Position::new(vec![0]),
+		Position::new(vec![0, 0]),
+		Position::new(vec![1])
+	]);
+	assert_eq!(system.rewrite_at(&term, &Position::new(vec![0])), Some(t("first(pair(a, a), loop)")));
+	assert!(system.is_normal_form(&t("pair(b, c)")));
+}
+
+#[test]
+fn strategies() {
+	let system = lazy();
+	let term = t("first(double(a), loop)");
+
+	let innermost = system.normalize(&term, Strategy::Innermost, 100);
+	assert!(!innermost.normal);
+	assert_eq!(innermost.steps, 100);
+
+	for strategy in [Strategy::Outermost, Strategy::Leftmost, Strategy::ParallelOutermost] {
+		let result = system.normalize(&term, strategy, 100);
+		assert!(result.normal);
+		assert_eq!(result.term, t("pair(b, b)"));
+	}
+
+	assert_eq!(system.step(&t("pair(a, double(a))"), Strategy::ParallelOutermost), Some(t("pair(b, pair(a, a))")));
+	assert_eq!(system.step(&t("pair(a, double(a))"), Strategy::Innermost), Some(t("pair(b, double(a))")));
+	assert_eq!(system.step(&t("pair(b, c)"), Strategy::Leftmost), None);
+}
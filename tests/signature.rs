@@ -0,0 +1,75 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use terms::{Term, Pattern, Signature, SignatureError};
+use terms::signature::SignatureErrorKind;
+use common::*;
+
+fn t(s: &str) -> Term<String> {
+	s.parse().unwrap()
+}
+
+fn signature() -> Signature<String> {
+	vec![("a".to_string(), 0), ("g".to_string(), 1), ("f".to_string(), 2)].into_iter().collect()
+}
+
+fn error(position: &str, kind: SignatureErrorKind<String>) -> SignatureError<String> {
+	SignatureError {
+		position: position.parse().unwrap(),
+		kind
+	}
+}
+
+#[test]
+fn declarations() {
+	let mut signature = signature();
+	assert_eq!(signature.len(), 3);
+	assert_eq!(signature.arity(&"f".to_string()), Some(2));
+	assert_eq!(signature.arity(&"h".to_string()), None);
+	assert_eq!(signature.add("g".to_string(), 3), Some(1));
+	assert_eq!(signature.max_arity(), Some(3));
+	assert_eq!(signature.constants().collect::<Vec<_>>(), vec!["a"]);
+
+	let ranked = Signature::from_ranked(&[Symbol('a'), Symbol('g'), Symbol('h')]);
+	assert_eq!(ranked.arity(&Symbol('h')), Some(3));
+	assert!(ranked.check_term(&term("h(a, g(a), a)")).is_ok());
+}
+
+#[test]
+fn arity_errors() {
+	let signature = signature();
+	assert_eq!(signature.check_term(&t("f(a, g(a))")), Ok(()));
+	assert_eq!(signature.check_term(&t("f(a)")), Err(error("", SignatureErrorKind::Arity("f".to_string(), 2, 1))));
+	assert_eq!(signature.check_term(&t("f(a, g(a, a))")), Err(error("1", SignatureErrorKind::Arity("g".to_string(), 1, 2))));
+	assert_eq!(signature.check_term(&t("g(f(a, g))")), Err(error("0.1", SignatureErrorKind::Arity("g".to_string(), 1, 0))));
+	assert_eq!(signature.check_term(&t("f(g(a), g(b))")), Err(error("1.0", SignatureErrorKind::UnknownSymbol("b".to_string()))));
+
+	// The first error in pre-order is reported.
+	assert_eq!(signature.check_term(&t("f(g(b), g)")), Err(error("0.0", SignatureErrorKind::UnknownSymbol("b".to_string()))));
+
+	let e = signature.check_term(&t("f(a, g(a, a))")).unwrap_err();
+	assert_eq!(e.to_string(), "symbol g expects 1 argument(s), but 2 are given at position 1");
+}
+
+#[test]
+fn patterns() {
+	let signature = signature();
+	let p = |s: &str| -> Pattern<String, String> { s.parse().unwrap() };
+	assert_eq!(signature.check_pattern(&p("f(?x, g(?y))")), Ok(()));
+	assert_eq!(signature.check_pattern(&p("f(?x, f(?y))")), Err(error("1", SignatureErrorKind::Arity("f".to_string(), 2, 1))));
+	assert_eq!(signature.check_pattern(&p("g(h(?x))")), Err(error("0", SignatureErrorKind::UnknownSymbol("h".to_string()))));
+}
+
+#[test]
+fn constructors() {
+	let signature = signature();
+	let a = signature.term("a".to_string(), vec![]).unwrap();
+	assert_eq!(signature.term("g".to_string(), vec![a.clone()]), Ok(t("g(a)")));
+	assert_eq!(signature.term("f".to_string(), vec![a.clone()]), Err(error("", SignatureErrorKind::Arity("f".to_string(), 2, 1))));
+
+	let x: Pattern<String, String> = Pattern::var("x".to_string());
+	assert!(signature.pattern("f".to_string(), vec![x.clone(), x.clone()]).is_ok());
+	assert_eq!(signature.pattern("h".to_string(), vec![x]).unwrap_err(), error("", SignatureErrorKind::UnknownSymbol("h".to_string())));
+}
@@ -0,0 +1,98 @@
+extern crate terms;
+
+use terms::{Term, Pattern, Position, SortedSignature, SortError};
+use terms::sort::SortErrorKind;
+
+fn t(s: &str) -> Term<String> {
+	s.parse().unwrap()
+}
+
+fn p(s: &str) -> Pattern<String, String> {
+	s.parse().unwrap()
+}
+
+/// Naturals are integers, and integers can be compared into booleans.
+fn signature() -> SortedSignature<String, &'static str> {
+	let mut signature = SortedSignature::new();
+	signature.add("zero".to_string(), vec![], "Nat");
+	signature.add("succ".to_string(), vec!["Nat"], "Nat");
+	signature.add("neg".to_string(), vec!["Int"], "Int");
+	signature.add("plus".to_string(), vec!["Int", "Int"], "Int");
+	signature.add("eq".to_string(), vec!["Int", "Int"], "Bool");
+	signature.add("not".to_string(), vec!["Bool"], "Bool");
+	signature.add("true".to_string(), vec![], "Bool");
+	signature.add("if".to_string(), vec!["Bool", "Int", "Int"], "Int");
+	signature.add_subsort("Nat", "Int");
+	signature.add_subsort("Int", "Num");
+	signature
+}
+
+fn error(position: &str, kind: SortErrorKind<String, &'static str>) -> SortError<String, &'static str> {
+	SortError {
+		position: position.parse().unwrap(),
+		kind
+	}
+}
+
+#[test]
+fn subsorts() {
+	let signature = signature();
+	assert!(signature.is_subsort(&"Nat", &"Nat"));
+	assert!(signature.is_subsort(&"Nat", &"Int"));
+	assert!(signature.is_subsort(&"Nat", &"Num"));
+	assert!(!signature.is_subsort(&"Int", &"Nat"));
+	assert!(!signature.is_subsort(&"Bool", &"Int"));
+}
+
+#[test]
+fn terms() {
+	let signature = signature();
+	assert_eq!(signature.sort_of_term(&t("succ(zero)")), Ok("Nat"));
+	assert_eq!(signature.sort_of_term(&t("plus(succ(zero), neg(zero))")), Ok("Int"));
+	assert_eq!(signature.sort_of_term(&t("not(eq(zero, neg(zero)))")), Ok("Bool"));
+
+	assert_eq!(signature.check_term(&t("succ(zero)"), &"Int"), Ok(()));
+	assert_eq!(signature.check_term(&t("neg(zero)"), &"Nat"), Err(error("", SortErrorKind::Mismatch("Nat", "Int"))));
+}
+
+#[test]
+fn term_errors() {
+	let signature = signature();
+	assert_eq!(signature.sort_of_term(&t("succ(neg(zero))")), Err(error("0", SortErrorKind::Mismatch("Nat", "Int"))));
+	assert_eq!(signature.sort_of_term(&t("plus(zero, eq(zero, true))")), Err(error("1.1", SortErrorKind::Mismatch("Int", "Bool"))));
+	assert_eq!(signature.sort_of_term(&t("plus(zero, neg(one))")), Err(error("1.0", SortErrorKind::UnknownSymbol("one".to_string()))));
+	assert_eq!(signature.sort_of_term(&t("neg(succ(zero, zero))")), Err(error("0", SortErrorKind::Arity("succ".to_string(), 1, 2))));
+
+	// Errors are found bottom-up.
+	assert_eq!(signature.sort_of_term(&t("succ(succ(true))")), Err(error("0.0", SortErrorKind::Mismatch("Nat", "Bool"))));
+
+	let e = signature.sort_of_term(&t("succ(neg(zero))")).unwrap_err();
+	assert_eq!(e.to_string(), "expected sort Nat, found Int at position 0");
+}
+
+#[test]
+fn variable_sorts() {
+	let signature = signature();
+	assert_eq!(signature.check_pattern(&p("eq(?x, ?y)"), None), Ok(vec![("x".to_string(), "Int"), ("y".to_string(), "Int")]));
+
+	// The smallest sort is inferred, whatever the order of the occurrences.
+	assert_eq!(signature.check_pattern(&p("plus(?x, succ(?x))"), None), Ok(vec![("x".to_string(), "Nat")]));
+	assert_eq!(signature.check_pattern(&p("plus(succ(?x), ?x)"), None), Ok(vec![("x".to_string(), "Nat")]));
+	assert_eq!(signature.check_pattern(&p("plus(?x, if(true, ?y, succ(?x)))"), None), Ok(vec![("x".to_string(), "Nat"), ("y".to_string(), "Int")]));
+
+	assert_eq!(signature.check_pattern(&p("?x"), Some(&"Int")), Ok(vec![("x".to_string(), "Int")]));
+	assert_eq!(signature.check_pattern(&p("?x"), None), Ok(vec![]));
+	assert_eq!(signature.check_pattern(&p("succ(?x)"), Some(&"Int")), Ok(vec![("x".to_string(), "Nat")]));
+	assert_eq!(signature.sort_of_pattern(&p("succ(?x)")), Some(&"Nat"));
+	assert_eq!(signature.sort_of_pattern(&p("?x")), None);
+}
+
+#[test]
+fn pattern_errors() {
+	let signature = signature();
+	assert_eq!(signature.check_pattern(&p("if(?x, ?x, zero)"), None), Err(error("1", SortErrorKind::VariableConflict("Bool", "Int"))));
+	assert_eq!(signature.check_pattern(&p("plus(?x, eq(?y, ?z))"), None), Err(error("1", SortErrorKind::Mismatch("Int", "Bool"))));
+	assert_eq!(signature.check_pattern(&p("neg(?x)"), Some(&"Nat")), Err(error("", SortErrorKind::Mismatch("Nat", "Int"))));
+	assert_eq!(signature.check_pattern(&p("plus(?x, succ(?x, ?y))"), None), Err(error("1", SortErrorKind::Arity("succ".to_string(), 1, 2))));
+	assert_eq!(error("1", SortErrorKind::Mismatch("Int", "Bool")).position, Position::root().child(1));
+}
@@ -0,0 +1,81 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use terms::{Pattern, Substitution};
+use common::*;
+
+fn substitution(bindings: &[(u32, &str)]) -> Substitution<Symbol, u32> {
+	bindings.iter().map(|(x, p)| (*x, pattern(p))).collect()
+}
+
+/// Random substitutions binding `?0` to `?3`, with variables in `?0` to `?5`.
+fn random_substitutions(seed: u64, count: usize) -> Vec<Substitution<Symbol, u32>> {
+	let patterns = random_patterns(seed, count * 4, 5, 6);
+	patterns.chunks(4).map(|chunk| {
+		chunk.iter().enumerate().filter(|(x, p)| **p != Pattern::var(*x as u32)).map(|(x, p)| (x as u32, p.clone())).collect()
+	}).collect()
+}
+
+#[test]
+fn composition() {
+	let sigma = substitution(&[(0, "f(?1, a)"), (2, "g(?0)")]);
+	let tau = substitution(&[(1, "b"), (0, "c"), (3, "?2")]);
+	let composed = sigma.compose(&tau);
+	assert_eq!(composed, substitution(&[(0, "f(b, a)"), (2, "g(c)"), (1, "b"), (3, "?2")]));
+
+	// Trivial bindings are removed.
+	let swap = substitution(&[(0, "?1"), (1, "?0")]);
+	assert!(swap.compose(&swap).is_empty());
+
+	let patterns = random_patterns(110, 50, 10, 6);
+	let substitutions = random_substitutions(111, 20);
+	for sigma in &substitutions {
+		for tau in &substitutions {
+			let composed = sigma.compose(tau);
+			for p in &patterns {
+				assert_eq!(composed.apply(p), tau.apply(&sigma.apply(p)));
+			}
+		}
+	}
+}
+
+#[test]
+fn normalization() {
+	// Triangular substitution, as built by unification.
+	let sigma = substitution(&[(0, "f(?1, a)"), (1, "g(?2)"), (3, "?3")]);
+	assert!(!sigma.is_idempotent());
+	let normalized = sigma.normalized();
+	assert_eq!(normalized, substitution(&[(0, "f(g(?2), a)"), (1, "g(?2)")]));
+	assert!(normalized.is_idempotent());
+
+	for p in random_patterns(112, 50, 10, 4) {
+		assert_eq!(normalized.apply(&p), sigma.resolve(&p));
+		assert_eq!(normalized.apply(&normalized.apply(&p)), normalized.apply(&p));
+	}
+
+	// Unifiers are triangular.
+	let a = pattern("f(?0, g(?1))");
+	let b = pattern("f(g(?2), ?0)");
+	let mut unifier = Substitution::new();
+	a.unify_with(&b, &mut unifier).unwrap();
+	let normalized = unifier.normalized();
+	assert!(normalized.is_idempotent());
+	assert_eq!(normalized.apply(&a), normalized.apply(&b));
+	assert_eq!(normalized.normalized(), normalized);
+}
+
+#[test]
+fn restriction() {
+	let sigma = substitution(&[(0, "a"), (1, "g(?2)"), (2, "b")]);
+	assert_eq!(sigma.restrict(&[1, 3]), substitution(&[(1, "g(?2)")]));
+	assert_eq!(sigma.restrict(&[2, 0]), substitution(&[(2, "b"), (0, "a")]));
+	assert!(sigma.restrict(&[]).is_empty());
+
+	// The restriction to the variables of a pattern applies to it in the same way.
+	for p in random_patterns(113, 50, 10, 4) {
+		let variables: Vec<u32> = p.variables().cloned().collect();
+		assert_eq!(sigma.restrict(&variables).apply(&p), sigma.apply(&p));
+	}
+}
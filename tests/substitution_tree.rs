@@ -0,0 +1,117 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use terms::{Pattern, SubstitutionTree};
+use common::*;
+
+/// Values of the stored patterns unifiable with the query, by brute force.
+fn unifiable(patterns: &[Pattern<Symbol, u32>], q: &Pattern<Symbol, u32>) -> Vec<usize> {
+	// Stored variables are renamed apart from the query.
+	(0..patterns.len()).filter(|i| q.is_unifiable(&shift(&patterns[*i], 100))).collect()
+}
+
+fn sorted<'a, I: IntoIterator<Item = &'a usize>>(values: I) -> Vec<usize> {
+	let mut values: Vec<usize> = values.into_iter().cloned().collect();
+	values.sort();
+	values
+}
+
+#[test]
+fn insert_remove() {
+	let mut tree = SubstitutionTree::new();
+	assert!(tree.is_empty());
+	assert_eq!(tree.insert(pattern("f(?0, a)"), 1), None);
+	assert_eq!(tree.insert(pattern("f(?1, a)"), 2), None);
+	assert_eq!(tree.insert(pattern("f(g(?0), b)"), 3), None);
+	assert_eq!(tree.insert(pattern("f(?0, a)"), 4), Some(1));
+	assert_eq!(tree.len(), 3);
+
+	assert_eq!(tree.remove(&pattern("f(?0, a)")), Some(4));
+	assert_eq!(tree.remove(&pattern("f(?0, a)")), None);
+	assert_eq!(tree.remove(&pattern("f(?2, a)")), None);
+	assert_eq!(tree.len(), 2);
+	assert_eq!(sorted(tree.iter().map(|(_, v)| v)), vec![2, 3]);
+}
+
+#[test]
+fn insert_twice() {
+	let patterns = random_patterns(38, 300, 7, 3);
+	let mut tree = SubstitutionTree::new();
+	for (i, p) in patterns.iter().enumerate() {
+		assert_eq!(tree.insert(p.clone(), i), None);
+	}
+	assert_eq!(tree.len(), patterns.len());
+
+	// Several branches may generalize a stored pattern.
+	for (i, p) in patterns.iter().enumerate() {
+		assert_eq!(tree.insert(p.clone(), i + patterns.len()), Some(i));
+	}
+	assert_eq!(tree.len(), patterns.len());
+	assert_eq!(sorted(tree.iter().map(|(_, v)| v)), (patterns.len()..(2 * patterns.len())).collect::<Vec<_>>());
+}
+
+#[test]
+fn retrieval() {
+	let patterns = random_patterns(10, 300, 7, 3);
+	let tree: SubstitutionTree<_, _, _> = patterns.iter().cloned().enumerate().map(|(i, p)| (p, i)).collect();
+	assert_eq!(tree.len(), patterns.len());
+
+	let mut found = [0, 0, 0];
+	for q in random_patterns(11, 200, 7, 2) {
+		let retrieved = sorted(tree.unifiable(&q).into_iter().map(|(_, v)| v));
+		assert_eq!(retrieved, unifiable(&patterns, &q));
+		found[0] += retrieved.len();
+
+		let retrieved = sorted(tree.generalizations(&q).into_iter().map(|r| {
+			assert_eq!(r.substitution.apply(r.pattern), q);
+			r.value
+		}));
+		let expected: Vec<usize> = (0..patterns.len()).filter(|i| patterns[*i].matches_like(&q).is_some()).collect();
+		assert_eq!(retrieved, expected);
+		found[1] += retrieved.len();
+
+		let retrieved = sorted(tree.instances(&q).into_iter().map(|r| {
+			assert_eq!(&r.substitution.apply(&q), r.pattern);
+			r.value
+		}));
+		let expected: Vec<usize> = (0..patterns.len()).filter(|i| q.matches_like(&patterns[*i]).is_some()).collect();
+		assert_eq!(retrieved, expected);
+		found[2] += retrieved.len();
+	}
+
+	assert!(found.iter().all(|n| *n > 100));
+}
+
+#[test]
+fn removal() {
+	let patterns = random_patterns(12, 300, 6, 2);
+	let mut tree: SubstitutionTree<_, _, _> = patterns.iter().cloned().enumerate().map(|(i, p)| (p, i)).collect();
+
+	// Remove the patterns in an order unrelated to the insertion order.
+	let mut removed = vec![false; patterns.len()];
+	for k in 0..patterns.len() {
+		let i = (k * 7) % patterns.len();
+		if k % 3 != 0 && !removed[i] {
+			assert_eq!(tree.remove(&patterns[i]), Some(i));
+			removed[i] = true
+		}
+	}
+
+	let remaining: Vec<usize> = (0..patterns.len()).filter(|i| !removed[*i]).collect();
+	assert_eq!(tree.len(), remaining.len());
+	assert_eq!(sorted(tree.iter().map(|(_, v)| v)), remaining);
+
+	for q in random_patterns(13, 200, 6, 2) {
+		let retrieved = sorted(tree.unifiable(&q).into_iter().map(|(_, v)| v));
+		let expected: Vec<usize> = unifiable(&patterns, &q).into_iter().filter(|i| !removed[*i]).collect();
+		assert_eq!(retrieved, expected);
+	}
+
+	for i in remaining {
+		assert_eq!(tree.remove(&patterns[i]), Some(i));
+	}
+	assert!(tree.is_empty());
+	assert_eq!(tree.iter().count(), 0);
+}
@@ -0,0 +1,115 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use terms::{Substitution, Theory, Axioms};
+use common::*;
+
+fn theory(axioms: &[(char, Axioms)]) -> Theory<Symbol> {
+	axioms.iter().map(|(f, a)| (Symbol(*f), *a)).collect()
+}
+
+fn solution(bindings: &[(u32, &str)]) -> Substitution<Symbol, u32> {
+	bindings.iter().map(|(x, p)| (*x, pattern(p))).collect()
+}
+
+#[test]
+fn flatten() {
+	let theory = theory(&[('m', Axioms::AssociativeCommutative), ('f', Axioms::Commutative)]);
+	assert_eq!(theory.flatten_term(&term("m(m(a, b), g(m(c, m(a, b))))")), term("m(a, b, g(m(c, a, b)))"));
+	assert_eq!(theory.flatten_term(&term("f(f(a, b), c)")), term("f(f(a, b), c)"));
+	assert_eq!(theory.flatten_pattern(&pattern("m(?0, m(?1, a))")), pattern("m(?0, ?1, a)"));
+}
+
+#[test]
+fn equivalent() {
+	let theory = theory(&[('m', Axioms::AssociativeCommutative), ('f', Axioms::Commutative), ('h', Axioms::Associative)]);
+	assert!(theory.equivalent(&term("m(m(a, b), c)"), &term("m(c, m(b, a))")));
+	assert!(theory.equivalent(&term("f(g(a), b)"), &term("f(b, g(a))")));
+	assert!(!theory.equivalent(&term("f(f(a, b), c)"), &term("f(a, f(b, c))")));
+	assert!(theory.equivalent(&term("h(h(a, b, c), b, c)"), &term("h(a, b, h(c, b, c))")));
+	assert!(!theory.equivalent(&term("h(a, b, c)"), &term("h(c, b, a)")));
+}
+
+#[test]
+fn ac_matching() {
+	let theory = theory(&[('m', Axioms::AssociativeCommutative)]);
+
+	// Each variable takes a non-empty part of `{a, b, c}`.
+	let solutions = theory.matches(&pattern("m(?0, ?1)"), &term("m(a, m(b, c))"));
+	assert_eq!(solutions.len(), 6);
+	for sigma in &solutions {
+		let t = sigma.instantiate(&pattern("m(?0, ?1)")).unwrap();
+		assert!(theory.equivalent(&t, &term("m(a, b, c)")))
+	}
+
+	assert_eq!(theory.matches(&pattern("m(?0, ?0, b)"), &term("m(a, b, a)")), vec![solution(&[(0, "a")])]);
+	assert_eq!(theory.matches(&pattern("m(g(?0), ?1)"), &term("m(a, g(b), c)")), vec![solution(&[(0, "b"), (1, "m(a, c)")])]);
+	assert!(theory.matches(&pattern("m(?0, ?1, ?2, ?3)"), &term("m(a, b, c)")).is_empty());
+}
+
+#[test]
+fn associative_matching() {
+	let theory = theory(&[('m', Axioms::Associative)]);
+	let solutions = theory.matches(&pattern("m(?0, ?1)"), &term("m(m(a, b), c)"));
+	assert_eq!(solutions, vec![
+		solution(&[(0, "a"), (1, "m(b, c)")]),
+		solution(&[(0, "m(a, b)"), (1, "c")])
+	]);
+
+	assert_eq!(theory.matches(&pattern("m(?0, b, ?0)"), &term("m(a, c, b, a, c)")), vec![solution(&[(0, "m(a, c)")])]);
+	assert!(theory.matches(&pattern("m(?0, a)"), &term("m(a, b)")).is_empty());
+}
+
+#[test]
+fn commutative_matching() {
+	let theory = theory(&[('f', Axioms::Commutative)]);
+	assert_eq!(theory.matches(&pattern("f(?0, ?1)"), &term("f(a, b)")).len(), 2);
+	assert_eq!(theory.matches(&pattern("f(g(?0), ?1)"), &term("f(a, g(b))")), vec![solution(&[(0, "b"), (1, "a")])]);
+
+	// Equal solutions are only reported once.
+	assert_eq!(theory.matches(&pattern("f(?0, ?1)"), &term("f(a, a)")).len(), 1);
+}
+
+#[test]
+fn commutative_unification() {
+	let theory = theory(&[('f', Axioms::Commutative), ('m', Axioms::AssociativeCommutative)]);
+
+	let unifiers = theory.unify(&pattern("f(?0, ?1)"), &pattern("f(a, b)"));
+	assert_eq!(unifiers.len(), 2);
+	assert!(unifiers.contains(&solution(&[(0, "a"), (1, "b")])));
+	assert!(unifiers.contains(&solution(&[(0, "b"), (1, "a")])));
+
+	// The arguments of associative-commutative symbols are permuted too.
+	assert_eq!(theory.unify(&pattern("m(?0, a)"), &pattern("m(a, b)")), vec![solution(&[(0, "b")])]);
+
+	assert!(theory.unify(&pattern("f(?0, g(?0))"), &pattern("f(g(a), b)")).is_empty());
+	assert!(theory.unify(&pattern("?0"), &pattern("f(a, ?0)")).is_empty());
+}
+
+#[test]
+fn canonical() {
+	let theory = theory(&[('m', Axioms::AssociativeCommutative), ('f', Axioms::Commutative), ('h', Axioms::Associative)]);
+	assert_eq!(theory.canonical(&term("m(c, m(b, a))")), term("m(a, b, c)"));
+	assert_eq!(theory.canonical(&term("m(c, m(b, a))")), theory.canonical(&term("m(m(a, c), b)")));
+	assert_ne!(theory.canonical(&term("h(a, b, c)")), theory.canonical(&term("h(c, b, a)")));
+
+	let mut canonical: Vec<_> = ["f(a, g(b))", "f(g(b), a)", "m(a, f(b, c))", "m(f(c, b), a)", "h(a, b, c)"].iter().map(|s| theory.canonical(&term(s))).collect();
+	canonical.sort();
+	canonical.dedup();
+	assert_eq!(canonical.len(), 3);
+}
+
+#[test]
+fn canonical_against_equivalent() {
+	let theory = theory(&[('f', Axioms::Commutative), ('h', Axioms::Associative)]);
+	let terms = random_terms(40, 200, 6);
+	for a in &terms {
+		let ca = theory.canonical(a);
+		assert!(theory.equivalent(a, &ca));
+		for b in &terms {
+			assert_eq!(ca == theory.canonical(b), theory.equivalent(a, b), "{} and {}", a, b);
+		}
+	}
+}
@@ -0,0 +1,66 @@
+extern crate terms;
+extern crate rand;
+
+mod common;
+
+use terms::Position;
+use common::*;
+
+fn show<'a, I: Iterator<Item = &'a terms::Term<Symbol>>>(iter: I) -> Vec<String> {
+	iter.map(|t| t.to_string()).collect()
+}
+
+#[test]
+fn orders() {
+	let t = term("f(g(a), h(b, c, g(a)))");
+	assert_eq!(show(t.pre_order()), vec!["f(g(a), h(b, c, g(a)))", "g(a)", "a", "h(b, c, g(a))", "b", "c", "g(a)", "a"]);
+	assert_eq!(show(t.post_order()), vec!["a", "g(a)", "b", "c", "a", "g(a)", "h(b, c, g(a))", "f(g(a), h(b, c, g(a)))"]);
+	assert_eq!(show(t.level_order()), vec!["f(g(a), h(b, c, g(a)))", "g(a)", "h(b, c, g(a))", "a", "b", "c", "g(a)", "a"]);
+	assert_eq!(t.symbols().map(|f| f.0).collect::<String>(), "fgahbcga");
+
+	let p = pattern("f(?0, h(?1, a, ?0))");
+	assert_eq!(p.symbols().map(|f| f.0).collect::<String>(), "fha");
+	assert_eq!(p.variable_occurrences().cloned().collect::<Vec<_>>(), vec![0, 1, 0]);
+	assert_eq!(p.variables().cloned().collect::<Vec<_>>(), vec![0, 1]);
+}
+
+#[test]
+fn depths_and_positions() {
+	let t = term("f(g(a), b)");
+	let depths: Vec<usize> = t.pre_order().with_depths().map(|(d, _)| d).collect();
+	assert_eq!(depths, vec![0, 1, 2, 1]);
+	let depths: Vec<usize> = t.level_order().with_depths().map(|(d, _)| d).collect();
+	assert_eq!(depths, vec![0, 1, 1, 2]);
+
+	let positions: Vec<String> = t.post_order().with_positions().map(|(p, _)| p.to_string()).collect();
+	assert_eq!(positions, vec!["0.0", "0", "1", "ε"]);
+
+	for t in random_terms(100, 100, 12) {
+		let n = t.size();
+		assert_eq!(t.pre_order().count(), n);
+		assert_eq!(t.post_order().count(), n);
+		assert_eq!(t.level_order().count(), n);
+
+		for (position, sub) in t.pre_order().with_positions().chain(t.post_order().with_positions()).chain(t.level_order().with_positions()) {
+			assert_eq!(t.at(&position), Some(sub));
+		}
+
+		for (depth, sub) in t.post_order().with_depths() {
+			assert!(depth as u64 + sub.depth() <= t.depth());
+		}
+
+		// Breadth-first traversal visits the nodes by increasing depth.
+		let depths: Vec<usize> = t.level_order().with_depths().map(|(d, _)| d).collect();
+		assert!(depths.windows(2).all(|w| w[0] <= w[1]));
+		assert_eq!(*depths.last().unwrap() as u64, t.depth());
+	}
+}
+
+#[test]
+fn adapters_restart_from_the_root() {
+	let t = term("f(a, b)");
+	let mut iter = t.pre_order();
+	assert_eq!(iter.next(), Some(&t));
+	let positions: Vec<Position> = iter.with_positions().map(|(p, _)| p).collect();
+	assert_eq!(positions, vec![Position::root(), Position::new(vec![0]), Position::new(vec![1])]);
+}